@@ -0,0 +1,93 @@
+//! Axum Integration
+//!
+//! This module provides an [`axum`](https://docs.rs/axum) extractor for the status callbacks
+//! Sendblue posts to a message's `status_callback` URL. It is only available with the `axum`
+//! feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use axum::{routing::post, Router};
+//! use sendblue::axum::StatusCallback;
+//!
+//! async fn hook(callback: StatusCallback) {
+//!     println!("{} is now {:?}", callback.message_handle, callback.status);
+//! }
+//!
+//! let app: Router = Router::new().route("/sendblue/status", post(hook));
+//! ```
+
+use ::axum::{
+    async_trait,
+    body::Bytes,
+    extract::{rejection::BytesRejection, FromRequest, Request},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use std::ops::Deref;
+use thiserror::Error;
+
+use crate::models::MessageStatusCallback;
+
+/// Extractor for a status callback posted by Sendblue
+///
+/// Reads the request body and deserializes it into a `MessageStatusCallback`. Requests whose
+/// body can't be read or decoded are rejected with a `StatusCallbackRejection`.
+#[derive(Debug, Clone)]
+pub struct StatusCallback(pub MessageStatusCallback);
+
+impl StatusCallback {
+    /// Returns the decoded callback payload
+    pub fn into_inner(self) -> MessageStatusCallback {
+        self.0
+    }
+}
+
+impl Deref for StatusCallback {
+    type Target = MessageStatusCallback;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<S> FromRequest<S> for StatusCallback
+where
+    S: Send + Sync,
+{
+    type Rejection = StatusCallbackRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let body = Bytes::from_request(req, state).await?;
+        let callback = serde_json::from_slice(&body)?;
+        Ok(Self(callback))
+    }
+}
+
+/// Rejection returned when a `StatusCallback` can't be extracted
+///
+/// # Variants
+///
+/// * `Body` - The request body couldn't be read, responds with the status axum chose
+/// * `InvalidPayload` - The body isn't a valid status callback, responds with
+///   `422 Unprocessable Entity`
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum StatusCallbackRejection {
+    #[error("Failed to read request body: {0}")]
+    Body(#[from] BytesRejection),
+    #[error("Invalid status callback: {0}")]
+    InvalidPayload(#[from] serde_json::Error),
+}
+
+impl IntoResponse for StatusCallbackRejection {
+    fn into_response(self) -> Response {
+        match self {
+            StatusCallbackRejection::Body(rejection) => rejection.into_response(),
+            StatusCallbackRejection::InvalidPayload(_) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()).into_response()
+            }
+        }
+    }
+}
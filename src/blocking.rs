@@ -0,0 +1,226 @@
+//! Blocking Sendblue API Client
+//!
+//! This module provides a blocking wrapper around [`SendblueClient`] for consumers that don't
+//! run an async runtime, such as simple CLI tools. It is only available with the `blocking`
+//! feature.
+//!
+//! The blocking client owns a single-threaded Tokio runtime and drives the async client on it.
+//! It must not be used from within an async runtime, calls will panic there.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use sendblue::blocking::BlockingClient;
+//! use sendblue::models::GetMessagesParamsBuilder;
+//!
+//! let client = BlockingClient::new("your_api_key".into(), "your_api_secret".into());
+//!
+//! for message in client.messages_iter(GetMessagesParamsBuilder::new().build()) {
+//!     match message {
+//!         Ok(message) => println!("{}: {:?}", message.uuid, message.content),
+//!         Err(e) => eprintln!("Error retrieving messages: {:?}", e),
+//!     }
+//! }
+//! ```
+
+use crate::{
+    models::{
+        AccountInfo, EvaluateService, EvaluateServiceResponse, GetMessagesParams,
+        GetMessagesResponse, GroupId, GroupMessageResponse, MessageHandle, MessageResponse,
+        PhoneNumber, Reaction, RetrievedMessage, Status, TypingIndicatorResponse,
+    },
+    traits::SendableMessage,
+    SendblueClient, SendblueError,
+};
+use std::{fmt::Debug, vec};
+use tokio::runtime::{Builder, Runtime};
+
+/// Number of messages fetched per page when the params don't set a `limit`
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+/// Blocking client for the Sendblue API
+///
+/// Wraps a [`SendblueClient`] and runs its requests to completion on an internal runtime.
+#[derive(Debug)]
+pub struct BlockingClient {
+    inner: SendblueClient,
+    runtime: Runtime,
+}
+
+impl BlockingClient {
+    /// Creates a new `BlockingClient`
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The API key for authenticating with the Sendblue API
+    /// * `api_secret` - The API secret for authenticating with the Sendblue API
+    pub fn new(api_key: String, api_secret: String) -> Self {
+        Self::from(SendblueClient::new(api_key, api_secret))
+    }
+
+    /// Returns the wrapped async client
+    pub fn inner(&self) -> &SendblueClient {
+        &self.inner
+    }
+
+    /// Sends a message using the Sendblue API, see [`SendblueClient::send`]
+    pub fn send<T>(&self, message: &T) -> Result<T::ResponseType, SendblueError>
+    where
+        T: SendableMessage + Debug,
+        T::ResponseType: Debug,
+    {
+        self.runtime.block_on(self.inner.send(message))
+    }
+
+    /// Sends a text message to an existing group, see [`SendblueClient::send_to_group`]
+    pub fn send_to_group(
+        &self,
+        group_id: &GroupId,
+        content: &str,
+    ) -> Result<GroupMessageResponse, SendblueError> {
+        self.runtime
+            .block_on(self.inner.send_to_group(group_id, content))
+    }
+
+    /// Retrieves a page of messages, see [`SendblueClient::get_messages`]
+    pub fn get_messages(
+        &self,
+        params: GetMessagesParams,
+    ) -> Result<GetMessagesResponse, SendblueError> {
+        self.runtime.block_on(self.inner.get_messages(params))
+    }
+
+    /// Evaluates if a number can send/receive iMessages, see [`SendblueClient::evaluate_service`]
+    pub fn evaluate_service(
+        &self,
+        evaluate_service: &EvaluateService,
+    ) -> Result<EvaluateServiceResponse, SendblueError> {
+        self.runtime
+            .block_on(self.inner.evaluate_service(evaluate_service))
+    }
+
+    /// Sends a typing indicator, see [`SendblueClient::send_typing_indicator`]
+    pub fn send_typing_indicator(
+        &self,
+        number: &PhoneNumber,
+    ) -> Result<TypingIndicatorResponse, SendblueError> {
+        self.runtime
+            .block_on(self.inner.send_typing_indicator(number))
+    }
+
+    /// Reacts to a message with a tapback, see [`SendblueClient::send_reaction`]
+    pub fn send_reaction(
+        &self,
+        target_handle: &MessageHandle,
+        reaction: Reaction,
+    ) -> Result<MessageResponse, SendblueError> {
+        self.runtime
+            .block_on(self.inner.send_reaction(target_handle, reaction))
+    }
+
+    /// Retrieves the account information, see [`SendblueClient::account`]
+    pub fn account(&self) -> Result<AccountInfo, SendblueError> {
+        self.runtime.block_on(self.inner.account())
+    }
+
+    /// Cancels a scheduled message, see [`SendblueClient::cancel_scheduled`]
+    pub fn cancel_scheduled(&self, handle: &MessageHandle) -> Result<(), SendblueError> {
+        self.runtime.block_on(self.inner.cancel_scheduled(handle))
+    }
+
+    /// Returns an iterator over all messages matching `params`, fetching pages as needed
+    ///
+    /// Pages are requested starting at `params.offset` with `params.limit` messages each
+    /// (100 if unset) until a page comes back short. If the API returns a `next_cursor`, the
+    /// following pages are requested with that cursor instead, until a page comes without
+    /// one. A `status` filter is applied to every
+    /// page. If a request fails, the error is yielded once and the iterator ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters for filtering messages and the first page to fetch
+    pub fn messages_iter(
+        &self,
+        params: GetMessagesParams,
+    ) -> impl Iterator<Item = Result<RetrievedMessage, SendblueError>> + '_ {
+        MessagesIter::new(self, params)
+    }
+}
+
+impl From<SendblueClient> for BlockingClient {
+    fn from(inner: SendblueClient) -> Self {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create Tokio runtime");
+
+        Self { inner, runtime }
+    }
+}
+
+/// Iterator over the messages of consecutive `get_messages` pages
+struct MessagesIter<'a> {
+    client: &'a BlockingClient,
+    params: GetMessagesParams,
+    status: Option<Status>,
+    page: vec::IntoIter<RetrievedMessage>,
+    done: bool,
+}
+
+impl<'a> MessagesIter<'a> {
+    fn new(client: &'a BlockingClient, mut params: GetMessagesParams) -> Self {
+        // Filter here rather than in `get_messages`, so short pages can still be detected
+        let status = params.status.take();
+        params.limit = Some(params.limit.unwrap_or(DEFAULT_PAGE_SIZE));
+
+        Self {
+            client,
+            params,
+            status,
+            page: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+
+    fn fetch_page(&mut self) -> Result<(), SendblueError> {
+        let mut page = self.client.get_messages(self.params.clone())?;
+
+        match page.next_cursor.take() {
+            Some(cursor) => {
+                self.done = page.is_empty();
+                self.params.cursor = Some(cursor);
+            }
+            // A cursor was followed before, so its absence marks the last page
+            None if self.params.cursor.is_some() => self.done = true,
+            None => {
+                let fetched = page.len() as u32;
+                self.done = !page.has_more(self.params.limit.unwrap_or(DEFAULT_PAGE_SIZE));
+                self.params.offset = Some(self.params.offset.unwrap_or(0) + fetched);
+            }
+        }
+        self.page = page.messages.into_iter();
+
+        Ok(())
+    }
+}
+
+impl Iterator for MessagesIter<'_> {
+    type Item = Result<RetrievedMessage, SendblueError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            for message in self.page.by_ref() {
+                if self.status.as_ref().map_or(true, |s| &message.status == s) {
+                    return Some(Ok(message));
+                }
+            }
+            if self.done {
+                return None;
+            }
+            if let Err(e) = self.fetch_page() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Client Configuration
+//!
+//! This module provides a deserializable configuration for the Sendblue client, so settings can
+//! be loaded from TOML, environment variables or any other `serde` source and turned into a
+//! client with `SendblueClient::from_config`.
+
+use phonenumber::country;
+use serde::{Deserialize, Deserializer};
+use std::{
+    fmt::{self, Debug},
+    time::Duration,
+};
+
+use crate::{models::CallbackUrl, SendblueClientBuilder};
+
+/// Configuration for a `SendblueClient`
+///
+/// Only the credentials are required, every other setting falls back to the default of
+/// `SendblueClientBuilder`. Durations are given in milliseconds, regions as ISO 3166 codes such
+/// as `"US"`.
+///
+/// # Examples
+///
+/// ```
+/// use sendblue::{SendblueClient, SendblueConfig};
+///
+/// let config: SendblueConfig = serde_json::from_str(
+///     r#"{ "api_key": "your_api_key", "api_secret": "your_api_secret", "timeout_ms": 10000 }"#,
+/// )
+/// .unwrap();
+///
+/// let client = SendblueClient::from_config(config).unwrap();
+/// ```
+#[derive(Deserialize, Clone, Default)]
+pub struct SendblueConfig {
+    /// The API key for authentication
+    pub api_key: String,
+    /// The API secret for authentication
+    pub api_secret: String,
+    /// The base URL of the API (optional)
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Total timeout of a request in milliseconds (optional)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Maximum number of idle connections kept per host (optional)
+    #[serde(default)]
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long idle connections are kept in the pool in milliseconds (optional)
+    ///
+    /// Missing keeps the builder default, `null` keeps idle connections indefinitely. Formats
+    /// without `null`, such as TOML, can only set a timeout.
+    #[serde(default, deserialize_with = "present")]
+    pub pool_idle_timeout_ms: Option<Option<u64>>,
+    /// How long messages with an idempotency key are remembered in milliseconds (optional)
+    #[serde(default)]
+    pub idempotency_window_ms: Option<u64>,
+    /// Whether plain `http://` URLs are allowed, defaults to `false`
+    #[serde(default)]
+    pub allow_http: bool,
+    /// Region used to parse numbers without a country code (optional)
+    #[serde(default)]
+    pub default_region: Option<country::Id>,
+    /// Whether sends to opted-out recipients fail, defaults to `false`
+    #[serde(default)]
+    pub treat_opt_out_as_error: bool,
+    /// Whether the request body is attached to errors of rejected sends, defaults to `false`
+    #[serde(default)]
+    pub attach_request_body: bool,
+    /// Status callback used for messages that don't set their own (optional)
+    #[serde(default)]
+    pub default_status_callback: Option<CallbackUrl>,
+    /// Maximum size of a response body in bytes (optional)
+    #[serde(default)]
+    pub max_response_size: Option<usize>,
+    /// Maximum number of requests per second (optional)
+    #[serde(default)]
+    pub rate_limit: Option<u32>,
+    /// Number of messages `send_to_many` sends at once (optional)
+    #[serde(default)]
+    pub send_concurrency: Option<usize>,
+}
+
+impl SendblueConfig {
+    /// Creates a `SendblueClientBuilder` with the settings of this configuration applied
+    pub fn into_builder(self) -> SendblueClientBuilder {
+        let mut builder = SendblueClientBuilder::new(self.api_key, self.api_secret)
+            .allow_http(self.allow_http)
+            .treat_opt_out_as_error(self.treat_opt_out_as_error)
+            .attach_request_body(self.attach_request_body);
+        if let Some(base_url) = self.base_url {
+            builder = builder.base_url(base_url);
+        }
+        if let Some(timeout) = self.timeout_ms {
+            builder = builder.timeout(Duration::from_millis(timeout));
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout_ms {
+            builder = builder.pool_idle_timeout(timeout.map(Duration::from_millis));
+        }
+        if let Some(window) = self.idempotency_window_ms {
+            builder = builder.idempotency_window(Duration::from_millis(window));
+        }
+        if let Some(region) = self.default_region {
+            builder = builder.default_region(region);
+        }
+        if let Some(status_callback) = self.default_status_callback {
+            builder = builder.default_status_callback(status_callback);
+        }
+        if let Some(max) = self.max_response_size {
+            builder = builder.max_response_size(max);
+        }
+        if let Some(per_second) = self.rate_limit {
+            builder = builder.rate_limit(per_second);
+        }
+        if let Some(concurrency) = self.send_concurrency {
+            builder = builder.send_concurrency(concurrency);
+        }
+        builder
+    }
+}
+
+impl Debug for SendblueConfig {
+    /// Formats the configuration without exposing the API key or secret
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendblueConfig")
+            .field("api_key", &"***")
+            .field("api_secret", &"***")
+            .field("base_url", &self.base_url)
+            .field("timeout_ms", &self.timeout_ms)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("pool_idle_timeout_ms", &self.pool_idle_timeout_ms)
+            .field("idempotency_window_ms", &self.idempotency_window_ms)
+            .field("allow_http", &self.allow_http)
+            .field("default_region", &self.default_region)
+            .field("treat_opt_out_as_error", &self.treat_opt_out_as_error)
+            .field("attach_request_body", &self.attach_request_body)
+            .field("default_status_callback", &self.default_status_callback)
+            .field("max_response_size", &self.max_response_size)
+            .field("rate_limit", &self.rate_limit)
+            .field("send_concurrency", &self.send_concurrency)
+            .finish()
+    }
+}
+
+/// Deserializes a field that is present, so an explicit `null` becomes `Some(None)` while a
+/// missing field stays `None` through `#[serde(default)]`
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -8,26 +8,188 @@ use thiserror::Error;
 ///
 /// # Variants
 ///
-/// * `BadRequest` - Represents a bad request error with a message
+/// * `BadRequest` - Represents a bad request error with a message, keeping the `X-Request-Id`
+///   Sendblue assigned to the request and the request body if the client attaches it
 /// * `Unknown` - Represents an unknown error with a message
+/// * `Http` - Represents an error response without a more specific variant, keeping its status,
+///   the `X-Request-Id` Sendblue assigned to the request, and the request body if the client
+///   attaches it
 /// * `ValidationError` - Represents a validation error with a message
+/// * `Validation` - Represents failed field validations, keeping the per-field `ValidationErrors`
 /// * `ReqwestError` - Represents an error that occurred during a request
+/// * `DeadlineExceeded` - Represents a request that did not complete before its deadline
+/// * `UnexpectedContentType` - Represents a successful response whose body isn't JSON
+/// * `Decode` - Represents a response body that couldn't be decoded, keeping the `serde_json` error
+/// * `NotFound` - Represents a resource, e.g. a scheduled message, that doesn't exist, keeping
+///   the `X-Request-Id` if Sendblue reported it
+/// * `AlreadySent` - Represents a scheduled message that can no longer be cancelled
+/// * `MissingEnvVar` - Represents a required environment variable that is not set
+/// * `RecipientOptedOut` - Represents a recipient who has opted out of receiving messages
+/// * `Unauthorized` - Represents a request rejected because of invalid or missing credentials,
+///   keeping its status, `401` or `403`, and the `X-Request-Id`
+/// * `ResponseTooLarge` - Represents a response body larger than the client's limit
+/// * `MediaTooLarge` - Represents a media file larger than Sendblue accepts
+/// * `MediaUnreachable` - Represents a media file whose host answered with a non-`2xx` status
+/// * `Middleware` - Represents an error raised by a middleware, only with the `middleware` feature
 ///
 /// # Examples
 ///
 /// ```
-/// use sendblue::error::SendblueError;
+/// use sendblue::prelude::*;
 ///
-/// let error = SendblueError::BadRequest("Invalid request".into());
+/// let error = SendblueError::BadRequest {
+///     body: "Invalid request".into(),
+///     request_id: None,
+///     request_body: None,
+/// };
 /// ```
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum SendblueError {
-    #[error("Bad Request: {0}")]
-    BadRequest(String),
+    #[error("Bad Request: {body}")]
+    BadRequest {
+        body: String,
+        request_id: Option<String>,
+        request_body: Option<String>,
+    },
     #[error("Unknown error: {0}")]
     Unknown(String),
+    #[error("HTTP {status}: {body}")]
+    Http {
+        status: u16,
+        body: String,
+        request_id: Option<String>,
+        request_body: Option<String>,
+    },
     #[error("Validation error: {0}")]
     ValidationError(String),
+    #[error("Validation error: {0}")]
+    Validation(#[from] validator::ValidationErrors),
     #[error("Request error: {0}")]
     ReqwestError(#[from] reqwest::Error),
+    #[error("Deadline exceeded before the request completed")]
+    DeadlineExceeded,
+    #[error("Unexpected content type {content_type:?}: {body_snippet}")]
+    UnexpectedContentType {
+        content_type: Option<String>,
+        body_snippet: String,
+    },
+    #[error("Failed to decode response: {0}")]
+    Decode(#[from] serde_json::Error),
+    #[error("Not found: {body}")]
+    NotFound {
+        body: String,
+        request_id: Option<String>,
+    },
+    #[error("Message already sent: {0}")]
+    AlreadySent(String),
+    #[error("Environment variable {0} is not set")]
+    MissingEnvVar(String),
+    #[error("Recipient {number} has opted out")]
+    RecipientOptedOut { number: String },
+    #[error("Unauthorized: {body}")]
+    Unauthorized {
+        status: u16,
+        body: String,
+        request_id: Option<String>,
+    },
+    #[error("Response body exceeds the limit of {limit} bytes")]
+    ResponseTooLarge { limit: usize },
+    #[error("Media of {size} bytes exceeds the limit of {limit} bytes")]
+    MediaTooLarge { size: u64, limit: u64 },
+    #[error("Media is not reachable, the host answered with status {status}")]
+    MediaUnreachable { status: u16 },
+    #[cfg(feature = "middleware")]
+    #[error("Middleware error: {0}")]
+    Middleware(#[source] reqwest_middleware::Error),
+}
+
+#[cfg(feature = "middleware")]
+impl From<reqwest_middleware::Error> for SendblueError {
+    /// Unwraps errors of the underlying reqwest client, so they are reported as `ReqwestError`
+    fn from(error: reqwest_middleware::Error) -> Self {
+        match error {
+            reqwest_middleware::Error::Reqwest(e) => SendblueError::ReqwestError(e),
+            error => SendblueError::Middleware(error),
+        }
+    }
+}
+
+// Errors are returned from spawned tasks and boxed into `dyn Error + Send + Sync`
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SendblueError>();
+};
+
+impl SendblueError {
+    /// Returns true if the request failed because no connection could be established
+    pub fn is_connect(&self) -> bool {
+        matches!(self, SendblueError::ReqwestError(e) if e.is_connect())
+    }
+
+    /// Returns true if the request timed out, either in reqwest or against a deadline
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            SendblueError::ReqwestError(e) => e.is_timeout(),
+            SendblueError::DeadlineExceeded => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the HTTP status of the response that caused the error, if there was one
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            SendblueError::BadRequest { .. } => Some(400),
+            SendblueError::Unauthorized { status, .. }
+            | SendblueError::Http { status, .. }
+            | SendblueError::MediaUnreachable { status } => Some(*status),
+            SendblueError::NotFound { .. } => Some(404),
+            SendblueError::ReqwestError(e) => e.status().map(|status| status.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Returns the ID Sendblue assigned to the failed request, if the error carries one
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            SendblueError::BadRequest { request_id, .. }
+            | SendblueError::Unauthorized { request_id, .. }
+            | SendblueError::NotFound { request_id, .. }
+            | SendblueError::Http { request_id, .. } => request_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the body of the rejected request, if the client attached it
+    ///
+    /// Only set when the client was built with `attach_request_body(true)`.
+    pub fn request_body(&self) -> Option<&str> {
+        match self {
+            SendblueError::BadRequest { request_body, .. }
+            | SendblueError::Http { request_body, .. } => request_body.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Sets the request body of `BadRequest` and `Http` errors, other errors are returned as is
+    pub(crate) fn with_request_body(mut self, body: String) -> Self {
+        if let SendblueError::BadRequest { request_body, .. }
+        | SendblueError::Http { request_body, .. } = &mut self
+        {
+            *request_body = Some(body);
+        }
+        self
+    }
+
+    /// Returns true if retrying the same request may succeed
+    ///
+    /// This covers connection failures, timeouts, and responses with a `429 Too Many Requests`
+    /// or `5xx` status.
+    pub fn is_transient(&self) -> bool {
+        if self.is_connect() || self.is_timeout() {
+            return true;
+        }
+        self.status()
+            .is_some_and(|status| status >= 500 || status == 429)
+    }
 }
@@ -29,6 +29,10 @@
 //! - **Retrieve Messages**: Fetch message histories with filtering and pagination options.
 //! - **Evaluate Phone Numbers**: Check if a phone number can send/receive iMessages.
 //! - **Typing Indicators**: Send typing indicators to recipients.
+//! - **Tracing**: Every API call runs in a `tracing` span such as `sendblue.send`, recording the
+//!   endpoint, the response status and, at debug level, the recipient. Credentials are never
+//!   recorded. At trace level, request and response bodies are logged unredacted, which includes
+//!   message content and phone numbers.
 //!
 //! # Installation
 //!
@@ -44,6 +48,38 @@
 //! cargo add sendblue --features schemars
 //! ```
 //!
+//! By default the client uses the platform's native TLS implementation (OpenSSL on Linux).
+//! To use rustls instead, for example to build fully static musl binaries, disable the default
+//! `native-tls` feature and enable `rustls`:
+//!
+//! ```sh
+//! cargo add sendblue --no-default-features --features rustls
+//! ```
+//!
+//! The two TLS features are meant to be used one at a time; if both are enabled, rustls is used.
+//!
+//! For code that doesn't run an async runtime, the `blocking` feature adds
+//! `blocking::BlockingClient`, which wraps the async client and can iterate over all pages
+//! of message history:
+//!
+//! ```sh
+//! cargo add sendblue --features blocking
+//! ```
+//!
+//! To upload local media files to Sendblue instead of hosting them yourself, enable the
+//! `upload` feature, which adds `SendblueClient::upload_media`.
+//!
+//! To plug in a [`reqwest-middleware`](https://docs.rs/reqwest-middleware) stack, e.g. for
+//! retries or caching, enable the `middleware` feature and add each middleware with
+//! `SendblueClientBuilder::middleware`.
+//!
+//! Web services built on axum can enable the `axum` feature, which adds the
+//! `axum::StatusCallback` extractor for status callbacks posted by Sendblue.
+//!
+//! While keeping the models in sync with the API, the `strict` feature makes response types
+//! reject fields they don't model, so additions to the API show up as decode errors instead
+//! of being silently dropped. Leave it off in production.
+//!
 //! # Usage
 //!
 //! To use the Sendblue API client, create an instance of `SendblueClient` with your API key and secret.
@@ -59,21 +95,20 @@
 //! ## Sending a Message
 //!
 //! ```rust
-//! use sendblue::SendblueClient;
-//! use sendblue::models::MessageBuilder;
+//! use sendblue::prelude::*;
 //!
 //! #[tokio::main]
 //! async fn main() {
 //!     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
 //!
-//!     let message = MessageBuilder::new(phonenumber::parse(None, "+10722971673").unwrap())
+//!     let message = MessageBuilder::new("+14155552671")
 //!         .content("Hello, world!".into())
 //!         .build()
 //!         .unwrap();
 //!
 //!     match client.send(&message).await {
 //!         Ok(response) => println!("Message sent: {:?}", response),
-//!         Err(e) => error!("Error sending message: {:?}", e),
+//!         Err(e) => eprintln!("Error sending message: {:?}", e),
 //!     }
 //! }
 //! ```
@@ -81,8 +116,7 @@
 //! ## Retrieving Messages
 //!
 //! ```rust
-//! use sendblue::SendblueClient;
-//! use sendblue::models::GetMessagesParamsBuilder;
+//! use sendblue::prelude::*;
 //!
 //! #[tokio::main]
 //! async fn main() {
@@ -91,13 +125,13 @@
 //!     let params = GetMessagesParamsBuilder::new()
 //!         .limit(Some(50))
 //!         .offset(Some(0))
-//!         .number(Some(phonenumber::parse(None, "+10722971673").unwrap()))
+//!         .number(Some("+14155552671".into()))
 //!         .from_date(Some("2023-06-15 12:00:00".into()))
 //!         .build();
 //!
 //!     match client.get_messages(params).await {
 //!         Ok(response) => println!("Messages retrieved: {:?}", response.messages),
-//!         Err(e) => error!("Error retrieving messages: {:?}", e),
+//!         Err(e) => eprintln!("Error retrieving messages: {:?}", e),
 //!     }
 //! }
 //! ```
@@ -105,20 +139,19 @@
 //! ## Evaluating a Phone Number
 //!
 //! ```rust
-//! use sendblue::SendblueClient;
-//! use sendblue::models::EvaluateServiceBuilder;
+//! use sendblue::prelude::*;
 //!
 //! #[tokio::main]
 //! async fn main() {
 //!     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
 //!
 //!     let evaluate_service = EvaluateServiceBuilder::new()
-//!         .number(phonenumber::parse(None, "+10722971673").unwrap())
+//!         .number("+14155552671".parse().unwrap())
 //!         .build();
 //!
 //!     match client.evaluate_service(&evaluate_service).await {
 //!         Ok(response) => println!("Evaluation result: {:?}", response),
-//!         Err(e) => error!("Error evaluating number: {:?}", e),
+//!         Err(e) => eprintln!("Error evaluating number: {:?}", e),
 //!     }
 //! }
 //! ```
@@ -132,38 +165,91 @@
 //! async fn main() {
 //!     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
 //!
-//!     let number = phonenumber::parse(None, "+10722971673").unwrap();
+//!     let number = "+14155552671".parse().unwrap();
 //!
 //!     match client.send_typing_indicator(&number).await {
 //!         Ok(response) => println!("Typing indicator sent: {:?}", response),
-//!         Err(e) => error!("Error sending typing indicator: {:?}", e),
+//!         Err(e) => eprintln!("Error sending typing indicator: {:?}", e),
 //!     }
 //! }
 //! ```
 
 use crate::models::{
-    EvaluateService, EvaluateServiceResponse, GetMessagesParams, GetMessagesResponse,
-    TypingIndicatorResponse,
+    media_url::MAX_MEDIA_SIZE, AccountInfo, CallbackUrl, ContactSegments, DynMessage,
+    DynMessageResponse, EvaluateService, EvaluateServiceResponse, EvaluateServiceType,
+    GetMessagesParams, GetMessagesResponse, GroupId, GroupMessageResponse, MediaUrl,
+    MessageBuilder, MessageHandle, MessageResponse, Reaction, ResponseMeta, SendReaction,
+    TypingIndicator, TypingIndicatorResponse,
 };
-use reqwest::{header::HeaderMap, Client};
-use std::fmt::Debug;
-use tracing::error;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE},
+    Client, Method, Proxy, Response, StatusCode,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::{sync::Semaphore, task::JoinError};
+use tracing::{debug, error, field::Empty, instrument, trace, Level, Span};
 
+#[cfg(feature = "axum")]
+pub mod axum;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod config;
 pub mod errors;
 pub mod models;
 pub mod prelude;
 pub mod traits;
 
+pub use config::SendblueConfig;
 pub use errors::SendblueError;
+use models::PhoneNumber;
 pub use phonenumber;
-use traits::SendableMessage;
+use phonenumber::country;
+use traits::{SendableMessage, Url};
 
 static BASE_URL: &str = "https://api.sendblue.co/api";
 
+/// Maximum number of characters of an unexpected response body kept in errors
+const BODY_SNIPPET_LEN: usize = 200;
+
+/// Default idle timeout of pooled connections, matching reqwest's default
+const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Number of concurrent evaluations used by `segment_contacts`
+const SEGMENT_CONCURRENCY: usize = 8;
+
+/// Number of messages `send_to_many` sends at once by default
+const DEFAULT_SEND_CONCURRENCY: usize = 8;
+
+/// Default limit for the size of a response body, far above anything the API returns
+const DEFAULT_MAX_RESPONSE_SIZE: usize = 10 * 1024 * 1024;
+
+/// HTTP client used to send requests, wrapped in the middleware stack with the `middleware`
+/// feature
+#[cfg(not(feature = "middleware"))]
+pub type HttpClient = Client;
+/// HTTP client used to send requests, wrapped in the middleware stack with the `middleware`
+/// feature
+#[cfg(feature = "middleware")]
+pub type HttpClient = reqwest_middleware::ClientWithMiddleware;
+
+/// How long responses to messages with an idempotency key are remembered by default
+const DEFAULT_IDEMPOTENCY_WINDOW: Duration = Duration::from_secs(5 * 60);
+
 /// Client for the Sendblue API
 ///
 /// The `SendblueClient` struct provides methods for interacting with the Sendblue API.
 ///
+/// The client is cheap to clone: the underlying `reqwest::Client` keeps its connection pool
+/// behind an `Arc`, so every clone reuses the same pool. This makes it easy to store the
+/// client in shared application state and clone it per handler.
+///
 /// # Examples
 ///
 /// ```
@@ -171,11 +257,339 @@ static BASE_URL: &str = "https://api.sendblue.co/api";
 ///
 /// let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
 /// ```
+///
+/// Sharing the client between tasks:
+///
+/// ```no_run
+/// use sendblue::{models::MessageBuilder, SendblueClient};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+///
+///     let tasks: Vec<_> = ["+14155552671", "+12125551234"]
+///         .into_iter()
+///         .map(|number| {
+///             let client = client.clone();
+///             tokio::spawn(async move {
+///                 let message = MessageBuilder::new(number)
+///                     .content("Hello, world!".into())
+///                     .build()
+///                     .unwrap();
+///                 client.send(&message).await
+///             })
+///         })
+///         .collect();
+///
+///     for task in tasks {
+///         if let Err(e) = task.await.unwrap() {
+///             eprintln!("Error sending message: {:?}", e);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone)]
 pub struct SendblueClient {
     pub api_key: String,
     pub api_secret: String,
-    pub client: Client,
+    pub client: HttpClient,
     base_url: String,
+    default_headers: HeaderMap,
+    sent_messages: Arc<SentMessages>,
+    default_region: Option<country::Id>,
+    treat_opt_out_as_error: bool,
+    attach_request_body: bool,
+    default_status_callback: Option<CallbackUrl>,
+    max_response_size: usize,
+    send_concurrency: usize,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+// The client is shared across tasks and threads, keep it `Send + Sync`
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SendblueClient>();
+};
+
+impl Debug for SendblueClient {
+    /// Formats the client without exposing the API key or secret
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendblueClient")
+            .field("api_key", &"***")
+            .field("api_secret", &"***")
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builder for creating a `SendblueClient` with custom settings
+///
+/// Settings that are not set explicitly keep reqwest's defaults, so a client built without
+/// any customization behaves like one created with `SendblueClient::new`.
+///
+/// # Examples
+///
+/// ```
+/// use sendblue::SendblueClientBuilder;
+/// use std::time::Duration;
+///
+/// let client = SendblueClientBuilder::new("your_api_key".into(), "your_api_secret".into())
+///     .pool_max_idle_per_host(32)
+///     .pool_idle_timeout(Some(Duration::from_secs(30)))
+///     .build()
+///     .unwrap();
+/// ```
+pub struct SendblueClientBuilder {
+    api_key: String,
+    api_secret: String,
+    base_url: String,
+    pool_max_idle_per_host: usize,
+    pool_idle_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxies: Vec<Proxy>,
+    allow_http: bool,
+    idempotency_window: Duration,
+    default_region: Option<country::Id>,
+    treat_opt_out_as_error: bool,
+    attach_request_body: bool,
+    default_status_callback: Option<CallbackUrl>,
+    max_response_size: usize,
+    send_concurrency: usize,
+    rate_limit: Option<u32>,
+    #[cfg(feature = "middleware")]
+    middleware: Vec<Arc<dyn reqwest_middleware::Middleware>>,
+}
+
+impl SendblueClientBuilder {
+    pub fn new(api_key: String, api_secret: String) -> Self {
+        Self {
+            api_key,
+            api_secret,
+            base_url: BASE_URL.into(),
+            pool_max_idle_per_host: usize::MAX,
+            pool_idle_timeout: Some(DEFAULT_POOL_IDLE_TIMEOUT),
+            timeout: None,
+            proxies: Vec::new(),
+            allow_http: false,
+            idempotency_window: DEFAULT_IDEMPOTENCY_WINDOW,
+            default_region: None,
+            treat_opt_out_as_error: false,
+            attach_request_body: false,
+            default_status_callback: None,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            send_concurrency: DEFAULT_SEND_CONCURRENCY,
+            rate_limit: None,
+            #[cfg(feature = "middleware")]
+            middleware: Vec::new(),
+        }
+    }
+
+    /// Sets the base URL for the API
+    pub fn base_url(mut self, base_url: String) -> Self {
+        self.base_url = base_url;
+        self
+    }
+
+    /// Sets the maximum number of idle connections kept per host
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = max;
+        self
+    }
+
+    /// Sets how long idle connections are kept in the pool, `None` keeps them indefinitely
+    pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.pool_idle_timeout = timeout;
+        self
+    }
+
+    /// Sets the total timeout of a request, from connecting until the body has been read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Routes requests through a proxy, can be called multiple times to add more proxies
+    ///
+    /// HTTPS requests are tunneled through the proxy with `CONNECT`, so TLS still terminates
+    /// at Sendblue and the proxy never sees the API credentials or message content.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use reqwest::Proxy;
+    /// use sendblue::SendblueClientBuilder;
+    ///
+    /// let client = SendblueClientBuilder::new("your_api_key".into(), "your_api_secret".into())
+    ///     .proxy(Proxy::https("http://proxy.internal:3128").unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Allows plain `http://` URLs, e.g. for a local mock server
+    ///
+    /// By default the client only makes HTTPS requests, keep it that way in production.
+    pub fn allow_http(mut self, allow_http: bool) -> Self {
+        self.allow_http = allow_http;
+        self
+    }
+
+    /// Sets how long a message with an idempotency key is remembered, defaults to 5 minutes
+    ///
+    /// Sending a message with the same idempotency key and payload again within this window
+    /// after it was sent successfully returns the first response without making another request.
+    pub fn idempotency_window(mut self, window: Duration) -> Self {
+        self.idempotency_window = window;
+        self
+    }
+
+    /// Sets the region used by `SendblueClient::parse_number` for numbers without a country code
+    pub fn default_region(mut self, region: country::Id) -> Self {
+        self.default_region = Some(region);
+        self
+    }
+
+    /// Makes sends to opted-out recipients fail with `SendblueError::RecipientOptedOut`
+    ///
+    /// By default such sends return the `MessageResponse` with `opted_out` set.
+    pub fn treat_opt_out_as_error(mut self, treat_opt_out_as_error: bool) -> Self {
+        self.treat_opt_out_as_error = treat_opt_out_as_error;
+        self
+    }
+
+    /// Attaches the serialized request body to errors of rejected sends, defaults to off
+    ///
+    /// Applies to `SendblueError::BadRequest` and `SendblueError::Http` returned by `send`,
+    /// `send_with_headers` and `send_with_meta`, see `SendblueError::request_body`. Off by
+    /// default, since the body contains message content and is often logged with the error.
+    pub fn attach_request_body(mut self, attach_request_body: bool) -> Self {
+        self.attach_request_body = attach_request_body;
+        self
+    }
+
+    /// Sets the status callback used for messages that don't set their own
+    pub fn default_status_callback(mut self, status_callback: CallbackUrl) -> Self {
+        self.default_status_callback = Some(status_callback);
+        self
+    }
+
+    /// Sets the maximum size of a response body in bytes, defaults to 10 MiB
+    ///
+    /// Reading a larger body fails with `SendblueError::ResponseTooLarge`.
+    pub fn max_response_size(mut self, max: usize) -> Self {
+        self.max_response_size = max;
+        self
+    }
+
+    /// Sets how many messages `SendblueClient::send_to_many` sends at once, defaults to 8
+    ///
+    /// `0` is treated as `1`.
+    pub fn send_concurrency(mut self, concurrency: usize) -> Self {
+        self.send_concurrency = concurrency;
+        self
+    }
+
+    /// Limits the client to `per_second` requests per second, `0` disables the limit
+    ///
+    /// Requests wait for a permit before they are sent. Up to `per_second` requests can be
+    /// sent at once, after that they are spaced evenly. Clones of a client share the limit.
+    pub fn rate_limit(mut self, per_second: u32) -> Self {
+        self.rate_limit = Some(per_second);
+        self
+    }
+
+    /// Adds a middleware to the stack every request passes through, only with the `middleware`
+    /// feature
+    ///
+    /// Middleware runs in the order it was added, e.g. put tracing before retries to see each
+    /// attempt.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use reqwest::{Request, Response};
+    /// use reqwest_middleware::{Middleware, Next, Result};
+    /// use sendblue::SendblueClientBuilder;
+    ///
+    /// struct LogRequests;
+    ///
+    /// #[async_trait::async_trait]
+    /// impl Middleware for LogRequests {
+    ///     async fn handle(
+    ///         &self,
+    ///         req: Request,
+    ///         extensions: &mut http::Extensions,
+    ///         next: Next<'_>,
+    ///     ) -> Result<Response> {
+    ///         println!("{} {}", req.method(), req.url());
+    ///         next.run(req, extensions).await
+    ///     }
+    /// }
+    ///
+    /// let client = SendblueClientBuilder::new("your_api_key".into(), "your_api_secret".into())
+    ///     .middleware(LogRequests)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "middleware")]
+    pub fn middleware<M>(mut self, middleware: M) -> Self
+    where
+        M: reqwest_middleware::Middleware,
+    {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Builds the `SendblueClient`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SendblueClient, SendblueError>` - The configured client or an error if the
+    ///   underlying HTTP client could not be created
+    pub fn build(self) -> Result<SendblueClient, SendblueError> {
+        let mut builder = http_client_builder()
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .https_only(!self.allow_http);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        for proxy in self.proxies {
+            builder = builder.proxy(proxy);
+        }
+        let client = builder.build()?;
+        #[cfg(feature = "middleware")]
+        let client = self
+            .middleware
+            .into_iter()
+            .fold(
+                reqwest_middleware::ClientBuilder::new(client),
+                |builder, middleware| builder.with_arc(middleware),
+            )
+            .build();
+
+        Ok(SendblueClient {
+            api_key: self.api_key,
+            api_secret: self.api_secret,
+            client,
+            base_url: self.base_url,
+            default_headers: HeaderMap::new(),
+            sent_messages: Arc::new(SentMessages::new(self.idempotency_window)),
+            default_region: self.default_region,
+            treat_opt_out_as_error: self.treat_opt_out_as_error,
+            attach_request_body: self.attach_request_body,
+            default_status_callback: self.default_status_callback,
+            max_response_size: self.max_response_size,
+            send_concurrency: self.send_concurrency,
+            rate_limiter: self
+                .rate_limit
+                .filter(|&per_second| per_second > 0)
+                .map(|per_second| Arc::new(RateLimiter::new(per_second))),
+        })
+    }
 }
 
 impl SendblueClient {
@@ -201,8 +615,17 @@ impl SendblueClient {
         SendblueClient {
             api_key,
             api_secret,
-            client: Client::new(),
+            client: default_http_client(),
             base_url: BASE_URL.into(),
+            default_headers: HeaderMap::new(),
+            sent_messages: Arc::new(SentMessages::new(DEFAULT_IDEMPOTENCY_WINDOW)),
+            default_region: None,
+            treat_opt_out_as_error: false,
+            attach_request_body: false,
+            default_status_callback: None,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            send_concurrency: DEFAULT_SEND_CONCURRENCY,
+            rate_limiter: None,
         }
     }
 
@@ -218,16 +641,219 @@ impl SendblueClient {
     ///
     /// * `SendblueClient` - A new Sendblue client instance
     ///
-    /// This is a private function and not intended for public use.
+    /// This is a private function and not intended for public use. Only HTTPS URLs are allowed,
+    /// use `SendblueClientBuilder::allow_http` to point a client at a plain-HTTP mock server.
     pub fn new_with_url(api_key: String, api_secret: String, base_url: String) -> Self {
         SendblueClient {
             api_key,
             api_secret,
-            client: Client::new(),
+            client: default_http_client(),
             base_url,
+            default_headers: HeaderMap::new(),
+            sent_messages: Arc::new(SentMessages::new(DEFAULT_IDEMPOTENCY_WINDOW)),
+            default_region: None,
+            treat_opt_out_as_error: false,
+            attach_request_body: false,
+            default_status_callback: None,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            send_concurrency: DEFAULT_SEND_CONCURRENCY,
+            rate_limiter: None,
+        }
+    }
+
+    /// Creates a new Sendblue client from the `SB_API_KEY` and `SB_API_SECRET` environment
+    /// variables
+    ///
+    /// # Returns
+    ///
+    /// * `SendblueClient` - A new Sendblue client instance
+    /// * `SendblueError::MissingEnvVar` - If one of the variables is not set
+    /// * `SendblueError::ValidationError` - If one of the values isn't a valid header value
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sendblue::SendblueClient;
+    ///
+    /// let client = SendblueClient::from_env().unwrap();
+    /// ```
+    pub fn from_env() -> Result<Self, SendblueError> {
+        Self::from_env_prefixed("SB")
+    }
+
+    /// Creates a new Sendblue client from the `{prefix}_API_KEY` and `{prefix}_API_SECRET`
+    /// environment variables
+    ///
+    /// Useful for processes that hold clients for several Sendblue accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix of the variable names, e.g. `PROD` for `PROD_API_KEY`
+    ///
+    /// # Returns
+    ///
+    /// * `SendblueClient` - A new Sendblue client instance
+    /// * `SendblueError::MissingEnvVar` - If one of the variables is not set
+    /// * `SendblueError::ValidationError` - If one of the values isn't a valid header value, e.g.
+    ///   because it ends in a newline
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sendblue::SendblueClient;
+    ///
+    /// let production = SendblueClient::from_env_prefixed("PROD").unwrap();
+    /// let staging = SendblueClient::from_env_prefixed("STAGING").unwrap();
+    /// ```
+    pub fn from_env_prefixed(prefix: &str) -> Result<Self, SendblueError> {
+        let var = |name: &str| -> Result<String, SendblueError> {
+            let key = format!("{}_{}", prefix, name);
+            let value =
+                std::env::var(&key).map_err(|_| SendblueError::MissingEnvVar(key.clone()))?;
+            credential_header(&key, &value)?;
+            Ok(value)
+        };
+
+        Ok(Self::new(var("API_KEY")?, var("API_SECRET")?))
+    }
+
+    /// Creates a new Sendblue client from a `SendblueConfig`
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration, e.g. deserialized from a config file
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SendblueClient, SendblueError>` - The configured client or an error if the
+    ///   underlying HTTP client could not be created
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::{SendblueClient, SendblueConfig};
+    ///
+    /// let config = SendblueConfig {
+    ///     api_key: "your_api_key".into(),
+    ///     api_secret: "your_api_secret".into(),
+    ///     timeout_ms: Some(10_000),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let client = SendblueClient::from_config(config).unwrap();
+    /// ```
+    pub fn from_config(config: SendblueConfig) -> Result<Self, SendblueError> {
+        config.into_builder().build()
+    }
+
+    /// Creates a builder for configuring a Sendblue client
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The API key for authentication
+    /// * `api_secret` - The API secret for authentication
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::SendblueClient;
+    ///
+    /// let client = SendblueClient::builder("your_api_key".into(), "your_api_secret".into())
+    ///     .pool_max_idle_per_host(16)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(api_key: String, api_secret: String) -> SendblueClientBuilder {
+        SendblueClientBuilder::new(api_key, api_secret)
+    }
+
+    /// Adds a header that is sent with every request made by this client
+    ///
+    /// The `sb-api-key-id` and `sb-api-secret-key` authentication headers always take precedence
+    /// and can't be overwritten this way.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the header
+    /// * `value` - The value of the header
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use reqwest::header::{HeaderName, HeaderValue};
+    /// use sendblue::SendblueClient;
+    ///
+    /// let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into())
+    ///     .with_default_header(
+    ///         HeaderName::from_static("x-request-id"),
+    ///         HeaderValue::from_static("1234abcd"),
+    ///     );
+    /// ```
+    pub fn with_default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    /// Parses a phone number, falling back to the client's default region
+    ///
+    /// Numbers in E.164 format are parsed as is. Numbers in national format, e.g.
+    /// `(415) 555-2671`, are parsed in the region set with `SendblueClientBuilder::default_region`.
+    /// The result is normalized to E.164, so it equals the same number parsed from E.164 and can
+    /// be used as is with `MessageBuilder::new` or as a key of `group_by_number`.
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The phone number to parse
+    ///
+    /// # Returns
+    ///
+    /// * `PhoneNumber` - The parsed phone number
+    /// * `SendblueError::ValidationError` - If the number can't be parsed or isn't valid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::{phonenumber::country, SendblueClient};
+    ///
+    /// let client = SendblueClient::builder("your_api_key".into(), "your_api_secret".into())
+    ///     .default_region(country::US)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let number = client.parse_number("(415) 555-2671").unwrap();
+    /// assert_eq!(number.to_e164(), "+14155552671");
+    /// ```
+    pub fn parse_number(&self, number: &str) -> Result<PhoneNumber, SendblueError> {
+        let invalid =
+            || SendblueError::ValidationError(format!("Invalid phone number: {}", number));
+        match phonenumber::parse(self.default_region, number) {
+            Ok(parsed) if phonenumber::is_valid(&parsed) => PhoneNumber::from(parsed)
+                .to_e164()
+                .parse()
+                .map_err(|_| invalid()),
+            _ => Err(invalid()),
         }
     }
 
+    /// Builds the headers for a request from the default headers, `extra` and the
+    /// authentication headers, which are applied last so they can't be overwritten
+    ///
+    /// Fails with `SendblueError::ValidationError` if the API key or secret isn't a valid header
+    /// value, e.g. because it ends in a newline.
+    fn request_headers(&self, extra: &HeaderMap) -> Result<HeaderMap, SendblueError> {
+        let mut headers = self.default_headers.clone();
+        headers.extend(extra.clone());
+        headers.insert(
+            "sb-api-key-id",
+            credential_header("API key", &self.api_key)?,
+        );
+        headers.insert(
+            "sb-api-secret-key",
+            credential_header("API secret", &self.api_secret)?,
+        );
+        Ok(headers)
+    }
+
     /// Sends a message using the Sendblue API
     ///
     /// # Arguments
@@ -251,14 +877,14 @@ impl SendblueClient {
     /// async fn main() {
     ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
     ///
-    ///     let message = MessageBuilder::new(phonenumber::parse(None, "+10722971673").unwrap())
+    ///     let message = MessageBuilder::new("+14155552671")
     ///         .content("Hello, world!".into())
     ///         .build()
     ///         .unwrap();
     ///
     ///     match client.send(&message).await {
     ///         Ok(response) => println!("Message sent: {:?}", response),
-    ///         Err(e) => error!("Error sending message: {:?}", e),
+    ///         Err(e) => eprintln!("Error sending message: {:?}", e),
     ///     }
     /// }
     /// ```
@@ -274,14 +900,14 @@ impl SendblueClient {
     ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
     ///
     ///     let group_message = MessageBuilder::<GroupMessage>::new_group()
-    ///         .numbers(vec![phonenumber::parse(None, "+10722971673").unwrap(), phonenumber::parse(None, "+10722971673").unwrap()])
+    ///         .numbers(["+14155552671", "+14155552672"])
     ///         .content("Hello, group!".into())
     ///         .build()
     ///         .unwrap();
     ///
     ///     match client.send::<>(&group_message).await {
     ///         Ok(response) => println!("Group message sent: {:?}", response),
-    ///         Err(e) => error!("Error sending group message: {:?}", e),
+    ///         Err(e) => eprintln!("Error sending group message: {:?}", e),
     ///     }
     /// }
     /// ```
@@ -290,113 +916,468 @@ impl SendblueClient {
         T: SendableMessage + Debug,
         T::ResponseType: Debug,
     {
-        let url = format!("{}{}", self.base_url, T::endpoint());
-        let mut headers = HeaderMap::new();
-        headers.insert("sb-api-key-id", self.api_key.parse().unwrap());
-        headers.insert("sb-api-secret-key", self.api_secret.parse().unwrap());
-
-        let response = self
-            .client
-            .post(&url)
-            .headers(headers)
-            .json(message)
-            .send()
-            .await?;
-
-        let status = response.status();
-        let response_text = response.text().await.unwrap_or_default();
-
-        match status {
-            reqwest::StatusCode::ACCEPTED => {
-                match serde_json::from_str::<T::ResponseType>(&response_text) {
-                    Ok(message_response) => Ok(message_response),
-                    Err(e) => {
-                        error!("Error decoding response: {}", e);
-                        error!("Response body: {}", response_text);
-                        Err(SendblueError::Unknown(format!(
-                            "Failed to decode response: {}",
-                            e
-                        )))
-                    }
-                }
-            }
-            reqwest::StatusCode::BAD_REQUEST => {
-                error!("Bad request: {}", response_text);
-                Err(SendblueError::BadRequest(response_text))
-            }
-            _ => {
-                error!(
-                    "Unhandled Status: {}\nResponse body: {}",
-                    status, response_text
-                );
-                error!("Please open an issue on https://github.com/NewtTheWolf/sendblue-rs/issues");
-                Err(SendblueError::Unknown(response_text))
-            }
-        }
+        self.send_with_headers(message, &HeaderMap::new()).await
     }
 
-    /// Retrieves messages using the Sendblue API
+    /// Sends a message using the Sendblue API with additional headers
+    ///
+    /// The extra headers are merged with the client's default headers. The authentication
+    /// headers always take precedence and can't be overwritten.
     ///
     /// # Arguments
     ///
-    /// * `params` - The parameters for filtering and paginating messages
+    /// * `message` - The message to be sent
+    /// * `headers` - Additional headers for this request
     ///
     /// # Returns
     ///
-    /// * `GetMessagesResponse` - The response containing the retrieved messages
+    /// * `MessageResponse` - The response from the Sendblue API
     /// * `SendblueError` - An error that occurred during the request
     ///
     /// # Examples
     ///
     /// ```
+    /// use reqwest::header::{HeaderMap, HeaderValue};
     /// use sendblue::SendblueClient;
-    /// use sendblue::models::{GetMessagesParamsBuilder};
+    /// use sendblue::models::MessageBuilder;
     ///
     /// #[tokio::main]
     /// async fn main() {
     ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
     ///
-    ///     let params = GetMessagesParamsBuilder::new()
-    ///         .limit(Some(50))
-    ///         .offset(Some(0))
-    ///         .number(Some(phonenumber::parse(None, "+10722971673").unwrap()))
-    ///         .from_date(Some("2023-06-15 12:00:00".into()))
-    ///         .cid(None)
-    ///         .build();
+    ///     let message = MessageBuilder::new("+14155552671")
+    ///         .content("Hello, world!".into())
+    ///         .build()
+    ///         .unwrap();
     ///
-    ///     match client.get_messages(params).await {
-    ///         Ok(response) => println!("Messages retrieved: {:?}", response.messages),
-    ///         Err(e) => error!("Error retrieving messages: {:?}", e),
+    ///     let mut headers = HeaderMap::new();
+    ///     headers.insert("x-request-id", HeaderValue::from_static("1234abcd"));
+    ///
+    ///     match client.send_with_headers(&message, &headers).await {
+    ///         Ok(response) => println!("Message sent: {:?}", response),
+    ///         Err(e) => eprintln!("Error sending message: {:?}", e),
     ///     }
     /// }
     /// ```
-    pub async fn get_messages(
+    #[instrument(
+        name = "sendblue.send",
+        skip_all,
+        fields(endpoint = T::endpoint(), recipient = Empty, status = Empty)
+    )]
+    pub async fn send_with_headers<T>(
         &self,
-        params: GetMessagesParams,
+        message: &T,
+        headers: &HeaderMap,
+    ) -> Result<T::ResponseType, SendblueError>
+    where
+        T: SendableMessage + Debug,
+        T::ResponseType: Debug,
+    {
+        record_recipient(|| message.recipient());
+
+        let dedup_key = match message.idempotency_key() {
+            Some(key) => {
+                let body = serde_json::to_string(message).map_err(|e| {
+                    SendblueError::ValidationError(format!("Failed to serialize message: {}", e))
+                })?;
+                Some(format!("{}\n{}", key, body))
+            }
+            None => None,
+        };
+        // Holding the slot makes concurrent sends with the same key wait for this one
+        let mut sent = match dedup_key {
+            Some(key) => Some(self.sent_messages.slot(key).lock_owned().await),
+            None => None,
+        };
+        if let Some(cached) = sent
+            .as_deref()
+            .and_then(|sent| self.sent_messages.fresh(sent))
+        {
+            debug!("Skipping duplicate send, returning the cached response");
+            return self.check_opt_out::<T>(decode_body(cached)?);
+        }
+
+        let response = self.post_message(message, headers).await?;
+        let body = self
+            .response_body(response)
+            .await
+            .map_err(|e| self.with_request_body(e, message))?;
+        if let Some(sent) = sent.as_mut() {
+            **sent = Some((Instant::now(), body.clone()));
+        }
+
+        self.check_opt_out::<T>(decode_body(&body)?)
+    }
+
+    /// Sends a message using the Sendblue API and returns the response metadata alongside it
+    ///
+    /// The metadata holds the status code and all response headers, e.g. the rate-limit
+    /// headers, so callers can throttle before running into `429 Too Many Requests`.
+    /// Messages are always sent, even if they carry an idempotency key that was sent before,
+    /// since a cached response has no metadata to return.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to be sent
+    ///
+    /// # Returns
+    ///
+    /// * `(MessageResponse, ResponseMeta)` - The response from the Sendblue API and its metadata
+    /// * `SendblueError` - An error that occurred during the request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::SendblueClient;
+    /// use sendblue::models::MessageBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     let message = MessageBuilder::new("+14155552671")
+    ///         .content("Hello, world!".into())
+    ///         .build()
+    ///         .unwrap();
+    ///
+    ///     match client.send_with_meta(&message).await {
+    ///         Ok((response, meta)) => println!(
+    ///             "Message sent: {:?}, remaining requests: {:?}",
+    ///             response,
+    ///             meta.rate_limit_remaining()
+    ///         ),
+    ///         Err(e) => eprintln!("Error sending message: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    #[instrument(
+        name = "sendblue.send",
+        skip_all,
+        fields(endpoint = T::endpoint(), recipient = Empty, status = Empty)
+    )]
+    pub async fn send_with_meta<T>(
+        &self,
+        message: &T,
+    ) -> Result<(T::ResponseType, ResponseMeta), SendblueError>
+    where
+        T: SendableMessage + Debug,
+        T::ResponseType: Debug,
+    {
+        record_recipient(|| message.recipient());
+        let response = self.post_message(message, &HeaderMap::new()).await?;
+        let meta = ResponseMeta::from(&response);
+
+        let message_response = self
+            .process_response(response)
+            .await
+            .map_err(|e| self.with_request_body(e, message))?;
+        Ok((self.check_opt_out::<T>(message_response)?, meta))
+    }
+
+    /// Returns the endpoint URL and JSON body that `send` would post, without sending anything
+    ///
+    /// Useful for snapshot tests of message construction and for debugging payloads the API
+    /// rejects. No request is made and no credentials are included in the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to preview
+    ///
+    /// # Returns
+    ///
+    /// * `(String, serde_json::Value)` - The endpoint URL and the serialized request body
+    /// * `SendblueError` - An error that occurred while serializing the message
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::SendblueClient;
+    /// use sendblue::models::MessageBuilder;
+    ///
+    /// let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    /// let message = MessageBuilder::new("+14155552671")
+    ///     .content("Hello, world!".into())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let (url, body) = client.preview(&message).unwrap();
+    /// assert_eq!(url, "https://api.sendblue.co/api/send-message");
+    /// assert_eq!(body["content"], "Hello, world!");
+    /// ```
+    pub fn preview<T>(&self, message: &T) -> Result<(String, serde_json::Value), SendblueError>
+    where
+        T: SendableMessage,
+    {
+        let url = format!("{}{}", self.base_url, T::endpoint());
+        let body = self.request_body(message)?;
+
+        Ok((url, body))
+    }
+
+    /// Sends a message using the Sendblue API, failing if it does not complete before `deadline`
+    ///
+    /// The whole request, including connecting and decoding the response, must finish before the
+    /// deadline. Requests are currently made in a single attempt, so the entire remaining budget
+    /// is given to that attempt.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to be sent
+    /// * `deadline` - The point in time by which the send must have completed
+    ///
+    /// # Returns
+    ///
+    /// * `MessageResponse` - The response from the Sendblue API
+    /// * `SendblueError` - An error that occurred during the request, or
+    ///   `SendblueError::DeadlineExceeded` if the deadline passed first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::SendblueClient;
+    /// use sendblue::models::MessageBuilder;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     let message = MessageBuilder::new("+14155552671")
+    ///         .content("Your code is 123456".into())
+    ///         .build()
+    ///         .unwrap();
+    ///
+    ///     let deadline = Instant::now() + Duration::from_secs(3);
+    ///     match client.send_before(&message, deadline).await {
+    ///         Ok(response) => println!("Message sent: {:?}", response),
+    ///         Err(e) => eprintln!("Error sending message: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn send_before<T>(
+        &self,
+        message: &T,
+        deadline: Instant,
+    ) -> Result<T::ResponseType, SendblueError>
+    where
+        T: SendableMessage + Debug,
+        T::ResponseType: Debug,
+    {
+        tokio::time::timeout_at(deadline.into(), self.send(message))
+            .await
+            .map_err(|_| SendblueError::DeadlineExceeded)?
+    }
+
+    /// Sends an individual or group message wrapped in a `DynMessage`
+    ///
+    /// Useful for workers that process a queue of pending sends of mixed kinds.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to be sent
+    ///
+    /// # Returns
+    ///
+    /// * `DynMessageResponse` - The response from the Sendblue API, of the same kind as the
+    ///   message
+    /// * `SendblueError` - An error that occurred during the request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::SendblueClient;
+    /// use sendblue::models::{DynMessage, MessageBuilder};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     let message: DynMessage = MessageBuilder::new("+14155552671")
+    ///         .content("Hello, world!".into())
+    ///         .build()
+    ///         .unwrap()
+    ///         .into();
+    ///
+    ///     match client.send_dyn(&message).await {
+    ///         Ok(response) => println!("Message sent: {:?}", response),
+    ///         Err(e) => eprintln!("Error sending message: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn send_dyn(
+        &self,
+        message: &DynMessage,
+    ) -> Result<DynMessageResponse, SendblueError> {
+        match message {
+            DynMessage::Message(message) => {
+                self.send(message).await.map(DynMessageResponse::Message)
+            }
+            DynMessage::Group(message) => self.send(message).await.map(DynMessageResponse::Group),
+        }
+    }
+
+    /// Sends the same content to several recipients, one individual message each
+    ///
+    /// Unlike a `GroupMessage`, which creates a single group chat, every recipient receives the
+    /// message in their own conversation and can't see the other recipients. The messages are
+    /// sent concurrently, at most as many at a time as set with
+    /// `SendblueClientBuilder::send_concurrency`. A task is only spawned once a slot is free, so
+    /// large recipient lists don't pile up pending requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `numbers` - The recipients' phone numbers
+    /// * `content` - The content sent to every recipient
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Result<MessageResponse, SendblueError>>` - One result per recipient, in the order
+    ///   of `numbers`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::SendblueClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     let numbers = ["+14155552671", "+12125551234"]
+    ///         .into_iter()
+    ///         .map(|number| number.parse().unwrap());
+    ///
+    ///     for result in client.send_to_many(numbers, "The store opens at 9am").await {
+    ///         match result {
+    ///             Ok(response) => println!("Message sent: {:?}", response),
+    ///             Err(e) => eprintln!("Error sending message: {:?}", e),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn send_to_many(
+        &self,
+        numbers: impl IntoIterator<Item = PhoneNumber>,
+        content: &str,
+    ) -> Vec<Result<MessageResponse, SendblueError>> {
+        self.spawn_bounded(numbers, self.send_concurrency, |client, number| {
+            let message = MessageBuilder::new(number)
+                .content(content.to_owned())
+                .build();
+            async move { client.send(&message?).await }
+        })
+        .await
+    }
+
+    /// Sends a text message to an existing group chat
+    ///
+    /// The group is addressed by its ID alone, so its participants don't have to be known. Use
+    /// `MessageBuilder::new_group` to also attach media or set a send style.
+    ///
+    /// # Arguments
+    ///
+    /// * `group_id` - The ID of the group, e.g. from a previous `GroupMessageResponse`
+    /// * `content` - The content of the message
+    ///
+    /// # Returns
+    ///
+    /// * `Result<GroupMessageResponse, SendblueError>` - The response from the Sendblue API or
+    ///   an error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::{models::GroupId, SendblueClient};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     let group_id = GroupId::from("66e3b90d-4447-43c6-9439-15a69408ac2");
+    ///
+    ///     match client.send_to_group(&group_id, "See you all at 7").await {
+    ///         Ok(response) => println!("Group message sent: {:?}", response),
+    ///         Err(e) => eprintln!("Error sending group message: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn send_to_group(
+        &self,
+        group_id: &GroupId,
+        content: &str,
+    ) -> Result<GroupMessageResponse, SendblueError> {
+        let message = MessageBuilder::new_group()
+            .group_id(group_id.clone())
+            .content(content.to_owned())
+            .build()?;
+        self.send(&message).await
+    }
+
+    /// Retrieves messages using the Sendblue API
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters for filtering and paginating messages. A `status` filter
+    ///   is applied client-side after the page is fetched.
+    ///
+    /// # Returns
+    ///
+    /// * `GetMessagesResponse` - The response containing the retrieved messages
+    /// * `SendblueError` - An error that occurred during the request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::SendblueClient;
+    /// use sendblue::models::{GetMessagesParamsBuilder};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     let params = GetMessagesParamsBuilder::new()
+    ///         .limit(Some(50))
+    ///         .offset(Some(0))
+    ///         .number(Some("+14155552671".into()))
+    ///         .from_date(Some("2023-06-15 12:00:00".into()))
+    ///         .cid(None)
+    ///         .build();
+    ///
+    ///     match client.get_messages(params).await {
+    ///         Ok(response) => println!("Messages retrieved: {:?}", response.messages),
+    ///         Err(e) => eprintln!("Error retrieving messages: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    #[instrument(
+        name = "sendblue.get_messages",
+        skip_all,
+        fields(endpoint = "/accounts/messages", status = Empty)
+    )]
+    pub async fn get_messages(
+        &self,
+        params: GetMessagesParams,
     ) -> Result<GetMessagesResponse, SendblueError> {
         let url = format!("{}/accounts/messages", self.base_url);
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert("sb-api-key-id", self.api_key.parse().unwrap());
-        headers.insert("sb-api-secret-key", self.api_secret.parse().unwrap());
 
+        self.wait_for_rate_limit().await;
         let response = self
             .client
             .get(&url)
-            .headers(headers)
+            .headers(self.request_headers(&HeaderMap::new())?)
             .query(&params)
             .send()
             .await?;
 
-        match response.status() {
-            reqwest::StatusCode::OK => {
-                let messages_response = response.json::<GetMessagesResponse>().await?;
-                Ok(messages_response)
-            }
-            reqwest::StatusCode::BAD_REQUEST => {
-                Err(SendblueError::BadRequest(response.text().await?))
-            }
-            _ => Err(SendblueError::Unknown(response.text().await?)),
+        let mut messages: GetMessagesResponse = self.process_response(response).await?;
+        if let Some(status) = &params.status {
+            messages
+                .messages
+                .retain(|message| &message.status == status);
         }
+
+        Ok(messages)
     }
 
     /// Evaluates if a number can send/receive iMessages using the Sendblue API
@@ -421,54 +1402,53 @@ impl SendblueClient {
     ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
     ///
     /// let evaluate_service = EvaluateServiceBuilder::new()
-    ///     .number(phonenumber::parse(None, "+10722971673").unwrap())
+    ///     .number("+14155552671".parse().unwrap())
     ///     .build();
     ///
     ///     match client.evaluate_service(&evaluate_service).await {
     ///         Ok(response) => println!("Evaluation result: {:?}", response),
-    ///         Err(e) => error!("Error evaluating number: {:?}", e),
+    ///         Err(e) => eprintln!("Error evaluating number: {:?}", e),
     ///     }
     /// }
     /// ```
+    #[instrument(
+        name = "sendblue.evaluate_service",
+        skip_all,
+        fields(endpoint = "/evaluate-service", recipient = Empty, status = Empty)
+    )]
     pub async fn evaluate_service(
         &self,
         evaluate_service: &EvaluateService,
     ) -> Result<EvaluateServiceResponse, SendblueError> {
         let url = format!("{}/evaluate-service", self.base_url);
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert("sb-api-key-id", self.api_key.parse().unwrap());
-        headers.insert("sb-api-secret-key", self.api_secret.parse().unwrap());
+        record_recipient(|| Some(evaluate_service.number.to_string()));
 
+        self.wait_for_rate_limit().await;
         let response = self
             .client
             .get(&url)
-            .headers(headers)
+            .headers(self.request_headers(&HeaderMap::new())?)
             .query(&[("number", &evaluate_service.number.to_string())])
             .send()
             .await?;
 
-        match response.status() {
-            reqwest::StatusCode::OK => {
-                let service_response = response.json::<EvaluateServiceResponse>().await?;
-                Ok(service_response)
-            }
-            reqwest::StatusCode::BAD_REQUEST => {
-                Err(SendblueError::BadRequest(response.text().await?))
-            }
-            _ => Err(SendblueError::Unknown(response.text().await?)),
-        }
+        self.process_response(response).await
     }
 
-    /// Sends a typing indicator to a recipient using the Sendblue API
+    /// Evaluates several numbers concurrently, see `evaluate_service`
+    ///
+    /// At most `concurrency` evaluations run at the same time, `0` is treated as `1`. A task is
+    /// only spawned once a slot is free.
     ///
     /// # Arguments
     ///
-    /// * `number` - The recipient's phone number in E.164 format
+    /// * `numbers` - The phone numbers to evaluate
+    /// * `concurrency` - The maximum number of requests in flight
     ///
     /// # Returns
     ///
-    /// * `TypingIndicatorResponse` - The response from the Sendblue API
-    /// * `SendblueError` - An error that occurred during the request
+    /// * `Vec<Result<EvaluateServiceResponse, SendblueError>>` - One result per number, in the
+    ///   order of `numbers`
     ///
     /// # Examples
     ///
@@ -479,355 +1459,3679 @@ impl SendblueClient {
     /// async fn main() {
     ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
     ///
-    ///     let number = phonenumber::parse(None, "+10722971673").unwrap();
+    ///     let numbers = [
+    ///         "+14155552671".parse().unwrap(),
+    ///         "+12125551234".parse().unwrap(),
+    ///     ];
     ///
-    ///     match client.send_typing_indicator(&number).await {
-    ///         Ok(response) => println!("Typing indicator sent: {:?}", response),
-    ///         Err(e) => error!("Error sending typing indicator: {:?}", e),
+    ///     for result in client.evaluate_service_batch(&numbers, 4).await {
+    ///         match result {
+    ///             Ok(response) => println!("Evaluation result: {:?}", response),
+    ///             Err(e) => eprintln!("Error evaluating number: {:?}", e),
+    ///         }
     ///     }
     /// }
     /// ```
-    pub async fn send_typing_indicator(
+    pub async fn evaluate_service_batch(
         &self,
-        number: String,
-    ) -> Result<TypingIndicatorResponse, SendblueError> {
-        let url = format!("{}/send-typing-indicator", self.base_url);
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert("sb-api-key-id", self.api_key.parse().unwrap());
-        headers.insert("sb-api-secret-key", self.api_secret.parse().unwrap());
-        let body = serde_json::json!({ "number": number.to_string() });
+        numbers: &[PhoneNumber],
+        concurrency: usize,
+    ) -> Vec<Result<EvaluateServiceResponse, SendblueError>> {
+        self.spawn_bounded(numbers, concurrency, |client, number| {
+            let evaluate_service = EvaluateService {
+                number: number.clone(),
+            };
+            async move { client.evaluate_service(&evaluate_service).await }
+        })
+        .await
+    }
 
-        let response = self
-            .client
-            .post(&url)
-            .headers(headers)
-            .json(&body)
-            .send()
-            .await?;
+    /// Spawns `task` for every item, with at most `concurrency` tasks running at a time
+    ///
+    /// A task is only spawned once a permit is free, `0` is treated as `1`. The results keep the
+    /// order of `items`.
+    async fn spawn_bounded<I, F, Fut, T>(
+        &self,
+        items: I,
+        concurrency: usize,
+        mut task: F,
+    ) -> Vec<Result<T, SendblueError>>
+    where
+        I: IntoIterator,
+        F: FnMut(SendblueClient, I::Item) -> Fut,
+        Fut: Future<Output = Result<T, SendblueError>> + Send + 'static,
+        T: Send + 'static,
+    {
+        let permits = Arc::new(Semaphore::new(concurrency.max(1)));
+        let mut tasks = Vec::new();
+        for item in items {
+            let permit = Arc::clone(&permits)
+                .acquire_owned()
+                .await
+                .expect("semaphore is never closed");
+            let future = task(self.clone(), item);
+            tasks.push(tokio::spawn(async move {
+                let _permit = permit;
+                future.await
+            }));
+        }
 
-        match response.status() {
-            reqwest::StatusCode::OK => {
-                let typing_indicator_response = response.json::<TypingIndicatorResponse>().await?;
-                Ok(typing_indicator_response)
-            }
-            reqwest::StatusCode::BAD_REQUEST => {
-                Err(SendblueError::BadRequest(response.text().await?))
-            }
-            _ => Err(SendblueError::Unknown(response.text().await?)),
+        let mut results = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            results.push(join_task(task.await));
         }
+        results
     }
-}
 
-/* #[cfg(test)]
-mod tests {
-    use super::*;
-    use httpmock::prelude::*;
+    /// Splits numbers into those that can receive iMessages and those that can only receive SMS
+    ///
+    /// The numbers are evaluated concurrently with `evaluate_service_batch`. Numbers that
+    /// couldn't be evaluated end up in `errored` instead of failing the whole call.
+    ///
+    /// # Arguments
+    ///
+    /// * `numbers` - The phone numbers to segment
+    ///
+    /// # Returns
+    ///
+    /// * `ContactSegments` - The numbers grouped by service
+    /// * `SendblueError` - Currently never returned, errors are collected per number
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::SendblueClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     let numbers = vec![
+    ///         "+14155552671".parse().unwrap(),
+    ///         "+12125551234".parse().unwrap(),
+    ///     ];
+    ///
+    ///     match client.segment_contacts(numbers).await {
+    ///         Ok(segments) => println!("{} iMessage users", segments.imessage.len()),
+    ///         Err(e) => eprintln!("Error segmenting contacts: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn segment_contacts(
+        &self,
+        numbers: Vec<PhoneNumber>,
+    ) -> Result<ContactSegments, SendblueError> {
+        let results = self
+            .evaluate_service_batch(&numbers, SEGMENT_CONCURRENCY)
+            .await;
+
+        let mut segments = ContactSegments::default();
+        for (number, result) in numbers.into_iter().zip(results) {
+            match result {
+                Ok(response) if response.service == EvaluateServiceType::IMessage => {
+                    segments.imessage.push(number)
+                }
+                Ok(_) => segments.sms.push(number),
+                Err(e) => segments.errored.push((number, e)),
+            }
+        }
+        Ok(segments)
+    }
+
+    /// Sends a typing indicator to a recipient using the Sendblue API
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The recipient's phone number in E.164 format
+    ///
+    /// # Returns
+    ///
+    /// * `TypingIndicatorResponse` - The response from the Sendblue API
+    /// * `SendblueError` - An error that occurred during the request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::SendblueClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     let number = "+14155552671".parse().unwrap();
+    ///
+    ///     match client.send_typing_indicator(&number).await {
+    ///         Ok(response) => println!("Typing indicator sent: {:?}", response),
+    ///         Err(e) => eprintln!("Error sending typing indicator: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    #[instrument(
+        name = "sendblue.send_typing_indicator",
+        skip_all,
+        fields(endpoint = "/send-typing-indicator", recipient = Empty, status = Empty)
+    )]
+    pub async fn send_typing_indicator(
+        &self,
+        number: &PhoneNumber,
+    ) -> Result<TypingIndicatorResponse, SendblueError> {
+        let url = format!("{}/send-typing-indicator", self.base_url);
+        record_recipient(|| Some(number.to_string()));
+        let indicator = TypingIndicator {
+            number: number.clone(),
+        };
+        trace_request_body(&indicator);
+
+        self.wait_for_rate_limit().await;
+        let response = self
+            .client
+            .post(&url)
+            .headers(self.request_headers(&HeaderMap::new())?)
+            .json(&indicator)
+            .send()
+            .await?;
+
+        self.process_response(response).await
+    }
+
+    /// Reacts to a message with an iMessage tapback using the Sendblue API
+    ///
+    /// # Arguments
+    ///
+    /// * `target_handle` - The `message_handle` of the message to react to
+    /// * `reaction` - The tapback to send
+    ///
+    /// # Returns
+    ///
+    /// * `MessageResponse` - The response from the Sendblue API
+    /// * `SendblueError` - An error that occurred during the request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::{
+    ///     models::{MessageHandle, Reaction},
+    ///     SendblueClient,
+    /// };
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///     let handle = MessageHandle::from("dfd747ba-5600-4a8a-804a-a614a0fbc1c5");
+    ///
+    ///     match client.send_reaction(&handle, Reaction::Love).await {
+    ///         Ok(response) => println!("Reaction sent: {:?}", response),
+    ///         Err(e) => eprintln!("Error sending reaction: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    #[instrument(
+        name = "sendblue.send_reaction",
+        skip_all,
+        fields(endpoint = "/send-reaction", status = Empty)
+    )]
+    pub async fn send_reaction(
+        &self,
+        target_handle: &MessageHandle,
+        reaction: Reaction,
+    ) -> Result<MessageResponse, SendblueError> {
+        let url = format!("{}/send-reaction", self.base_url);
+        let request = SendReaction {
+            message_handle: target_handle.clone(),
+            reaction,
+        };
+        trace_request_body(&request);
+
+        self.wait_for_rate_limit().await;
+        let response = self
+            .client
+            .post(&url)
+            .headers(self.request_headers(&HeaderMap::new())?)
+            .json(&request)
+            .send()
+            .await?;
+
+        self.process_response(response).await
+    }
+
+    /// Checks that a media file can be fetched and isn't too large to send
+    ///
+    /// Issues a `HEAD` request to the media URL, without Sendblue credentials, and checks that
+    /// it answers with a `2xx` status and a `Content-Length` of at most
+    /// `models::media_url::MAX_MEDIA_SIZE`. A response without a `Content-Length`, or with one
+    /// that can't be parsed, passes the check, its size is then only checked by Sendblue. Call
+    /// this before `send` to catch broken media before Sendblue rejects the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the media file
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The media is reachable and small enough, or its size is unknown
+    /// * `SendblueError::MediaUnreachable` - The host answered with a non-`2xx` status, e.g.
+    ///   `404 Not Found`
+    /// * `SendblueError::MediaTooLarge` - The media is larger than Sendblue accepts
+    /// * `SendblueError` - Any other error that occurred during the request
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sendblue::{models::MediaUrl, traits::Url, SendblueClient};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     let media_url = MediaUrl::new("https://example.com/image.png").unwrap();
+    ///
+    ///     match client.validate_media(&media_url).await {
+    ///         Ok(()) => println!("Media is ready to send"),
+    ///         Err(e) => eprintln!("Media can't be sent: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    #[instrument(
+        name = "sendblue.validate_media",
+        skip_all,
+        fields(status = Empty)
+    )]
+    pub async fn validate_media(&self, url: &MediaUrl) -> Result<(), SendblueError> {
+        let response = self.client.head(url.as_str()).send().await?;
+
+        let status = response.status();
+        Span::current().record("status", status.as_u16());
+        if !status.is_success() {
+            return Err(SendblueError::MediaUnreachable {
+                status: status.as_u16(),
+            });
+        }
+
+        let size = response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        match size {
+            Some(size) if size > MAX_MEDIA_SIZE => Err(SendblueError::MediaTooLarge {
+                size,
+                limit: MAX_MEDIA_SIZE,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Uploads a media file to Sendblue and returns the URL it is hosted at
+    ///
+    /// The returned `MediaUrl` can be passed to `MessageBuilder::media_url`, so local files can
+    /// be sent without hosting them yourself. Requires the `upload` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The contents of the file
+    /// * `content_type` - The MIME type of the file, e.g. `image/png`
+    ///
+    /// # Returns
+    ///
+    /// * `MediaUrl` - The URL of the hosted file
+    /// * `SendblueError` - An error that occurred during the request
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sendblue::SendblueClient;
+    /// use sendblue::models::MessageBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     let bytes = std::fs::read("picture.png").unwrap();
+    ///     let media_url = client.upload_media(bytes.into(), "image/png").await.unwrap();
+    ///
+    ///     let message = MessageBuilder::new("+14155552671")
+    ///         .media_url(media_url)
+    ///         .build()
+    ///         .unwrap();
+    /// }
+    /// ```
+    #[instrument(
+        name = "sendblue.upload_media",
+        skip_all,
+        fields(endpoint = "/upload-file", status = Empty)
+    )]
+    #[cfg(feature = "upload")]
+    pub async fn upload_media(
+        &self,
+        bytes: bytes::Bytes,
+        content_type: &str,
+    ) -> Result<MediaUrl, SendblueError> {
+        #[derive(serde::Deserialize)]
+        struct UploadMediaResponse {
+            media_url: MediaUrl,
+        }
+
+        let url = format!("{}/upload-file", self.base_url);
+        let extension = MediaUrl::extension_for_mime(content_type).unwrap_or("bin");
+        let part = reqwest::multipart::Part::stream(bytes)
+            .file_name(format!("upload.{}", extension))
+            .mime_str(content_type)?;
+        let form = reqwest::multipart::Form::new().part("file", part);
+
+        self.wait_for_rate_limit().await;
+        let response = self
+            .client
+            .post(&url)
+            .headers(self.request_headers(&HeaderMap::new())?)
+            .multipart(form)
+            .send()
+            .await?;
+
+        let uploaded: UploadMediaResponse = self.process_response(response).await?;
+        Ok(uploaded.media_url)
+    }
+
+    /// Retrieves the account information using the Sendblue API
+    ///
+    /// **Unverified:** the `/account` endpoint isn't part of Sendblue's public API documentation,
+    /// and `AccountInfo` hasn't been checked against a real response. Expect `NotFound` or a
+    /// decoding error if your account doesn't expose it, and don't use it to check connectivity,
+    /// `health_check` does that against a documented endpoint.
+    ///
+    /// # Returns
+    ///
+    /// * `AccountInfo` - The plan, available numbers and usage of the account
+    /// * `SendblueError` - An error that occurred during the request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::SendblueClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     match client.account().await {
+    ///         Ok(account) => println!("Numbers available: {:?}", account.numbers),
+    ///         Err(e) => eprintln!("Error retrieving account: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    #[instrument(
+        name = "sendblue.account",
+        skip_all,
+        fields(endpoint = "/account", status = Empty)
+    )]
+    pub async fn account(&self) -> Result<AccountInfo, SendblueError> {
+        let url = format!("{}/account", self.base_url);
+
+        self.wait_for_rate_limit().await;
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.request_headers(&HeaderMap::new())?)
+            .send()
+            .await?;
+
+        self.process_response(response).await
+    }
+
+    /// Checks that the API is reachable and the credentials are valid
+    ///
+    /// Makes a single authenticated request for one message from the documented
+    /// `/accounts/messages` endpoint, which has no side effects, so applications can fail fast on
+    /// startup instead of on the first send.
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The API accepted the credentials
+    /// * `SendblueError` - `Unauthorized` if the credentials were rejected, or another error
+    ///   that occurred during the request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::{SendblueClient, SendblueError};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     match client.health_check().await {
+    ///         Ok(()) => println!("Connected to Sendblue"),
+    ///         Err(SendblueError::Unauthorized { .. }) => eprintln!("Invalid API credentials"),
+    ///         Err(e) => eprintln!("Sendblue is not reachable: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    #[instrument(
+        name = "sendblue.health_check",
+        skip_all,
+        fields(endpoint = "/accounts/messages", status = Empty)
+    )]
+    pub async fn health_check(&self) -> Result<(), SendblueError> {
+        let url = format!("{}/accounts/messages", self.base_url);
+
+        self.wait_for_rate_limit().await;
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.request_headers(&HeaderMap::new())?)
+            .query(&[("limit", 1)])
+            .send()
+            .await?;
+
+        self.process_response::<serde_json::Value>(response)
+            .await
+            .map(|_| ())
+    }
+
+    /// Cancels a scheduled message before it is delivered
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The `message_handle` of the response to the scheduled message
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The scheduled message was cancelled
+    /// * `SendblueError` - `NotFound` if there is no scheduled message with this handle,
+    ///   `AlreadySent` if it has already been sent, or another error that occurred during the
+    ///   request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::{models::MessageHandle, SendblueClient};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///     let handle = MessageHandle::from("dfd747ba-5600-4a8a-804a-a614a0fbc1c5");
+    ///
+    ///     match client.cancel_scheduled(&handle).await {
+    ///         Ok(()) => println!("Scheduled message cancelled"),
+    ///         Err(e) => eprintln!("Error cancelling scheduled message: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    #[instrument(
+        name = "sendblue.cancel_scheduled",
+        skip_all,
+        fields(endpoint = "/cancel-scheduled-message", status = Empty)
+    )]
+    pub async fn cancel_scheduled(&self, handle: &MessageHandle) -> Result<(), SendblueError> {
+        let url = format!("{}/cancel-scheduled-message", self.base_url);
+        let body = serde_json::json!({ "message_handle": handle });
+        trace_request_body(&body);
+
+        self.wait_for_rate_limit().await;
+        let response = self
+            .client
+            .post(&url)
+            .headers(self.request_headers(&HeaderMap::new())?)
+            .json(&body)
+            .send()
+            .await?;
+
+        Span::current().record("status", response.status().as_u16());
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            StatusCode::CONFLICT => {
+                Err(SendblueError::AlreadySent(self.read_body(response).await?))
+            }
+            _ => self
+                .process_response::<serde_json::Value>(response)
+                .await
+                .map(|_| ()),
+        }
+    }
+
+    /// Turns a response to an opted-out recipient into an error if the client is configured to
+    fn check_opt_out<T>(&self, response: T::ResponseType) -> Result<T::ResponseType, SendblueError>
+    where
+        T: SendableMessage,
+    {
+        match T::opted_out_recipient(&response) {
+            Some(number) if self.treat_opt_out_as_error => {
+                Err(SendblueError::RecipientOptedOut { number })
+            }
+            _ => Ok(response),
+        }
+    }
+
+    /// Sends a request to any API endpoint and returns the untouched response
+    ///
+    /// This is an escape hatch for endpoints without typed support yet. The base URL, the
+    /// default headers, the authentication headers and the rate limit are applied, but the
+    /// status code and body are left to the caller: error statuses are not turned into a
+    /// `SendblueError`.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method
+    /// * `path` - The path relative to the base URL, e.g. `/accounts/messages`
+    /// * `body` - An optional JSON body
+    ///
+    /// # Returns
+    ///
+    /// * `reqwest::Response` - The response as returned by the API
+    /// * `SendblueError` - An error that occurred while sending the request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use reqwest::Method;
+    /// use sendblue::SendblueClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+    ///
+    ///     match client.request_raw(Method::GET, "/beta/feature", None).await {
+    ///         Ok(response) => println!("Status: {}", response.status()),
+    ///         Err(e) => eprintln!("Error sending request: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    #[instrument(name = "sendblue.request_raw", skip_all, fields(endpoint = path))]
+    pub async fn request_raw(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<Response, SendblueError> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut request = self
+            .client
+            .request(method, &url)
+            .headers(self.request_headers(&HeaderMap::new())?);
+        if let Some(body) = body {
+            trace_request_body(&body);
+            request = request.json(&body);
+        }
+
+        self.wait_for_rate_limit().await;
+        Ok(request.send().await?)
+    }
+
+    /// Posts a message to its endpoint, merging `headers` into the request headers
+    async fn post_message<T>(
+        &self,
+        message: &T,
+        headers: &HeaderMap,
+    ) -> Result<Response, SendblueError>
+    where
+        T: SendableMessage,
+    {
+        let url = format!("{}{}", self.base_url, T::endpoint());
+        let body = self.request_body(message)?;
+        trace_request_body(&body);
+
+        self.wait_for_rate_limit().await;
+        Ok(self
+            .client
+            .post(&url)
+            .headers(self.request_headers(headers)?)
+            .json(&body)
+            .send()
+            .await?)
+    }
+
+    /// Serializes a message, filling in the default status callback if it doesn't set one
+    fn request_body<T>(&self, message: &T) -> Result<serde_json::Value, SendblueError>
+    where
+        T: SendableMessage,
+    {
+        let mut body = serde_json::to_value(message).map_err(|e| {
+            SendblueError::ValidationError(format!("Failed to serialize message: {}", e))
+        })?;
+        if let (Some(callback), Some(fields)) =
+            (&self.default_status_callback, body.as_object_mut())
+        {
+            fields
+                .entry("status_callback")
+                .or_insert_with(|| callback.as_str().into());
+        }
+
+        Ok(body)
+    }
+
+    /// Attaches the serialized `message` to `error`, if the client is configured to
+    fn with_request_body<T>(&self, error: SendblueError, message: &T) -> SendblueError
+    where
+        T: SendableMessage,
+    {
+        if !self.attach_request_body {
+            return error;
+        }
+        match self.request_body(message) {
+            Ok(body) => error.with_request_body(body.to_string()),
+            Err(_) => error,
+        }
+    }
+
+    /// Processes a response from the Sendblue API
+    ///
+    /// Successful responses are decoded into `R`, after checking that the body is JSON.
+    /// `204 No Content` responses decode into a unit-like `R` such as `()`.
+    /// Error statuses are mapped to the corresponding `SendblueError`: `401 Unauthorized` and
+    /// `403 Forbidden` to `Unauthorized`, `404 Not Found` to `NotFound`.
+    async fn process_response<R>(&self, response: Response) -> Result<R, SendblueError>
+    where
+        R: DeserializeOwned,
+    {
+        let body = self.response_body(response).await?;
+        decode_body(&body)
+    }
+
+    /// Returns the JSON body of a successful response, `null` for `204 No Content`
+    ///
+    /// Error statuses and non-JSON bodies are mapped to the corresponding `SendblueError`.
+    async fn response_body(&self, response: Response) -> Result<String, SendblueError> {
+        let status = response.status();
+        Span::current().record("status", status.as_u16());
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned());
+        let request_id = response
+            .headers()
+            .get("x-request-id")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+        let response_text = self.read_body(response).await?;
+        trace!("Response body: {}", response_text);
+
+        match status {
+            StatusCode::NO_CONTENT => Ok("null".into()),
+            StatusCode::OK | StatusCode::ACCEPTED | StatusCode::CREATED => {
+                if content_type.as_deref().is_some_and(|ct| !is_json(ct)) {
+                    error!(
+                        "Unexpected content type {:?}\nResponse body: {}",
+                        content_type, response_text
+                    );
+                    return Err(SendblueError::UnexpectedContentType {
+                        content_type,
+                        body_snippet: response_text.chars().take(BODY_SNIPPET_LEN).collect(),
+                    });
+                }
+                Ok(response_text)
+            }
+            StatusCode::BAD_REQUEST => {
+                error!(?request_id, "Bad request: {}", response_text);
+                Err(SendblueError::BadRequest {
+                    body: response_text,
+                    request_id,
+                    request_body: None,
+                })
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                error!(?request_id, "Unauthorized: {}", response_text);
+                Err(SendblueError::Unauthorized {
+                    status: status.as_u16(),
+                    body: response_text,
+                    request_id,
+                })
+            }
+            StatusCode::NOT_FOUND => {
+                error!(?request_id, "Not found: {}", response_text);
+                Err(SendblueError::NotFound {
+                    body: response_text,
+                    request_id,
+                })
+            }
+            _ => {
+                error!(
+                    ?request_id,
+                    "Unhandled Status: {}\nResponse body: {}", status, response_text
+                );
+                error!("Please open an issue on https://github.com/NewtTheWolf/sendblue-rs/issues");
+                Err(SendblueError::Http {
+                    status: status.as_u16(),
+                    body: response_text,
+                    request_id,
+                    request_body: None,
+                })
+            }
+        }
+    }
+
+    /// Waits for a permit from the rate limiter, if one is configured
+    async fn wait_for_rate_limit(&self) {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+    }
+
+    /// Reads a response body as text, failing once it grows beyond `max_response_size`
+    async fn read_body(&self, mut response: Response) -> Result<String, SendblueError> {
+        let limit = self.max_response_size;
+        if response
+            .content_length()
+            .is_some_and(|length| length > limit as u64)
+        {
+            return Err(SendblueError::ResponseTooLarge { limit });
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > limit {
+                return Err(SendblueError::ResponseTooLarge { limit });
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+}
+
+/// Slot of a message with an idempotency key, holding the response once it was sent
+///
+/// The slot is locked for the duration of a send, so concurrent sends of the same message wait
+/// for the first one instead of sending it again.
+type SentMessage = Arc<tokio::sync::Mutex<Option<(Instant, String)>>>;
+
+/// Responses to recently sent messages that carry an idempotency key
+///
+/// Sendblue has no server-side idempotency support, so duplicates are caught on the client.
+/// Entries are keyed by the idempotency key and the serialized message, and expire `window`
+/// after the message was sent. Only successful sends are remembered. Clones of a client share
+/// the same cache.
+struct SentMessages {
+    window: Duration,
+    entries: Mutex<HashMap<String, SentMessage>>,
+}
+
+impl SentMessages {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the slot for `key`, dropping expired and unused entries
+    fn slot(&self, key: String) -> SentMessage {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, slot| {
+            Arc::strong_count(slot) > 1
+                || slot
+                    .try_lock()
+                    .is_ok_and(|sent| self.fresh(&sent).is_some())
+        });
+        Arc::clone(entries.entry(key).or_default())
+    }
+
+    /// Returns the response body stored in a slot, unless it has expired
+    fn fresh<'a>(&self, sent: &'a Option<(Instant, String)>) -> Option<&'a str> {
+        sent.as_ref()
+            .filter(|(sent_at, _)| sent_at.elapsed() < self.window)
+            .map(|(_, body)| body.as_str())
+    }
+}
+
+/// Token bucket limiting how many requests are sent per second
+///
+/// The bucket holds up to `capacity` tokens and refills at `rate` tokens per second. Taking a
+/// token from an empty bucket reserves the next one, so waiting requests are spaced evenly.
+struct RateLimiter {
+    rate: f64,
+    capacity: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    fn new(per_second: u32) -> Self {
+        let capacity = f64::from(per_second);
+        Self {
+            rate: capacity,
+            capacity,
+            state: Mutex::new((capacity, Instant::now())),
+        }
+    }
+
+    async fn acquire(&self) {
+        let wait = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let (tokens, refilled_at) = *state;
+            let now = Instant::now();
+            let available = (tokens + now.duration_since(refilled_at).as_secs_f64() * self.rate)
+                .min(self.capacity);
+            *state = (available - 1.0, now);
+            (available < 1.0).then(|| Duration::from_secs_f64((1.0 - available) / self.rate))
+        };
+        if let Some(wait) = wait {
+            debug!("Rate limit reached, waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Converts a credential into a header value, without including the credential in the error
+fn credential_header(name: &str, value: &str) -> Result<HeaderValue, SendblueError> {
+    HeaderValue::from_str(value).map_err(|_| {
+        SendblueError::ValidationError(format!(
+            "{} contains characters that aren't allowed in a header",
+            name
+        ))
+    })
+}
+
+/// Decodes a JSON response body into `R`
+fn decode_body<R>(body: &str) -> Result<R, SendblueError>
+where
+    R: DeserializeOwned,
+{
+    serde_json::from_str(body).map_err(|e| {
+        error!("Error decoding response: {}", e);
+        error!("Response body: {}", body);
+        SendblueError::Decode(e)
+    })
+}
+
+/// Unwraps the result of a spawned task, resuming its panic if it panicked
+fn join_task<T>(result: Result<Result<T, SendblueError>, JoinError>) -> Result<T, SendblueError> {
+    match result {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(SendblueError::Unknown(e.to_string())),
+    }
+}
+
+/// Logs the JSON body of a request at trace level
+///
+/// This includes the message content, it is only serialized when trace-level logging is enabled.
+fn trace_request_body<T: Serialize + ?Sized>(body: &T) {
+    if tracing::enabled!(Level::TRACE) {
+        match serde_json::to_string(body) {
+            Ok(body) => trace!("Request body: {}", body),
+            Err(e) => trace!("Request body could not be serialized: {}", e),
+        }
+    }
+}
+
+/// Records the recipient on the current span, only if debug-level tracing is enabled
+fn record_recipient(recipient: impl FnOnce() -> Option<String>) {
+    if tracing::enabled!(Level::DEBUG) {
+        if let Some(recipient) = recipient() {
+            Span::current().record("recipient", recipient.as_str());
+        }
+    }
+}
+
+/// Creates the reqwest client builder with the TLS backend selected by the crate features
+///
+/// The `rustls` feature takes precedence if both TLS features are enabled. Only HTTPS requests
+/// are allowed, `SendblueClientBuilder::allow_http` lifts this for local testing.
+fn http_client_builder() -> reqwest::ClientBuilder {
+    let builder = Client::builder().https_only(true);
+    #[cfg(feature = "rustls")]
+    let builder = builder.use_rustls_tls();
+    builder
+}
+
+/// Creates the HTTP client of clients that aren't configured through `SendblueClientBuilder`
+fn default_http_client() -> HttpClient {
+    let client = http_client_builder()
+        .build()
+        .expect("failed to create HTTP client");
+    #[cfg(feature = "middleware")]
+    let client = HttpClient::from(client);
+    client
+}
+
+/// Returns `true` if a `Content-Type` header value denotes a JSON body
+fn is_json(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    mime.eq_ignore_ascii_case("application/json") || mime.to_ascii_lowercase().ends_with("+json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
     use models::{
-        EvaluateServiceBuilder, GetMessagesParamsBuilder, GroupMessage, MessageBuilder, Status,
-        TypingIndicatorStatus,
+        EvaluateServiceBuilder, GetMessagesParamsBuilder, GroupMessage, MediaUrl, MessageBuilder,
+        SendStyle, Status, TypingIndicatorStatus,
     };
-    use phonenumber::parse;
     use serde_json::json;
+    use traits::Url;
+
+    fn create_client_with_mock_url(base_url: &str) -> SendblueClient {
+        SendblueClient::builder("test_key".into(), "test_secret".into())
+            .base_url(base_url.into())
+            .allow_http(true)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_from_env_prefixed() {
+        std::env::set_var("SENDBLUE_TEST_ENV_API_KEY", "env_key");
+        std::env::set_var("SENDBLUE_TEST_ENV_API_SECRET", "env_secret");
+
+        let client = SendblueClient::from_env_prefixed("SENDBLUE_TEST_ENV").unwrap();
+        assert_eq!(client.api_key, "env_key");
+        assert_eq!(client.api_secret, "env_secret");
+
+        let error = SendblueClient::from_env_prefixed("SENDBLUE_TEST_MISSING").unwrap_err();
+        assert!(
+            matches!(error, SendblueError::MissingEnvVar(name) if name == "SENDBLUE_TEST_MISSING_API_KEY")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_credentials_are_rejected_without_panicking() {
+        std::env::set_var("SENDBLUE_TEST_NEWLINE_API_KEY", "env_key\n");
+        std::env::set_var("SENDBLUE_TEST_NEWLINE_API_SECRET", "env_secret");
+        let error = SendblueClient::from_env_prefixed("SENDBLUE_TEST_NEWLINE").unwrap_err();
+        assert!(matches!(error, SendblueError::ValidationError(_)));
+        assert!(!error.to_string().contains("env_key"));
+
+        let client = SendblueClient::new("test_key\n".into(), "test_secret".into());
+        assert!(matches!(
+            client.account().await,
+            Err(SendblueError::ValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_http_is_rejected_unless_allowed() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("GET").path("/account");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({}));
+        });
+
+        let client = SendblueClient::new_with_url(
+            "test_key".into(),
+            "test_secret".into(),
+            mock_server.base_url(),
+        );
+        let error = client.account().await.unwrap_err();
+        assert!(matches!(error, SendblueError::ReqwestError(_)));
+        mock.assert_hits(0);
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        assert!(client.account().await.is_ok());
+        mock.assert_hits(1);
+    }
+
+    #[test]
+    fn test_builder_with_proxy() {
+        let client = SendblueClient::builder("test_key".into(), "test_secret".into())
+            .proxy(Proxy::https("http://proxy.internal:3128").unwrap())
+            .proxy(Proxy::http("http://proxy.internal:3128").unwrap())
+            .build();
+        assert!(client.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_send_message_success() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("POST")
+                .path("/send-message")
+                .header("sb-api-key-id", "test_key")
+                .header("sb-api-secret-key", "test_secret");
+            then.status(202)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "accountEmail": "YOUR EMAIL",
+                    "content": "Hello world!",
+                    "is_outbound": true,
+                    "status": "QUEUED",
+                    "error_code": null,
+                    "error_message": null,
+                    "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+                    "date_sent": "2023-09-27T16:35:32.287Z",
+                    "date_updated": "2023-09-27T16:35:32.703Z",
+                    "from_number": "+16468528190",
+                    "number": "+16468528190",
+                    "to_number": "+16468528190",
+                    "was_downgraded": null,
+                    "plan": "dedicated",
+                    "media_url": "https://picsum.photos/200/300.jpg",
+                    "message_type": "message",
+                    "group_id": "",
+                    "participants": [],
+                    "send_style": "invisible",
+                    "opted_out": false,
+                    "error_detail": null
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let message = MessageBuilder::new("+14155552671")
+            .content("Test message".into())
+            .build()
+            .unwrap();
+
+        let result = client.send(&message).await;
+        if let Err(e) = &result {
+            error!("Error in test_send_message_success: {:?}", e);
+        }
+        assert!(result.is_ok());
+        let response = result.unwrap();
+        assert_eq!(response.status, Status::Queued);
+        assert_eq!(
+            response.message_handle.as_str(),
+            "dfd747ba-5600-4a8a-804a-a614a0fbc1c5"
+        );
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_success() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("GET")
+                .path("/accounts/messages")
+                .header("sb-api-key-id", "test_key")
+                .header("sb-api-secret-key", "test_secret");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "messages": [
+                        {
+                            "error_message": null,
+                            "date": "2023-09-21T20:22:05.066Z",
+                            "to_number": "+14155552671",
+                            "date_sent": {
+                                "_seconds": 1695327725,
+                                "_nanoseconds": 66000000
+                            },
+                            "date_updated": {
+                                "_seconds": 1695327725,
+                                "_nanoseconds": 456000000
+                            },
+                            "error_detail": null,
+                            "phoneID": "worker_5s_spacegray_1",
+                            "message_type": "message",
+                            "uuid": "595578e5-6701-4b89-ac9b-28cbfe99cd",
+                            "media_url": "",
+                            "content": "testn - Sent using sendblue.co",
+                            "send_style": "",
+                            "callback_url": "",
+                            "is_outbound": true,
+                            "allow_sms": false,
+                            "accountEmail": "youremail@gmail.com",
+                            "was_downgraded": null,
+                            "group_id": "",
+                            "from_number": "+88888888888",
+                            "error_code": 22,
+                            "row_id": "4444",
+                            "status": "ERROR"
+                        }
+                    ]
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let params = GetMessagesParamsBuilder::new().build();
+
+        let response = client.get_messages(params).await.unwrap();
+        assert_eq!(response.messages.len(), 1);
+        let message = &response.messages[0];
+        assert_eq!(message.message_type, models::MessageType::Message);
+        assert_eq!(message.send_style.as_deref(), Some(""));
+        assert_eq!(message.callback_url.as_deref(), Some(""));
+        assert_eq!(message.allow_sms, Some(false));
+        assert_eq!(message.phone_id.as_deref(), Some("worker_5s_spacegray_1"));
+        assert_eq!(message.error_code, Some(models::ErrorCode::Other(22)));
+        assert_eq!(message.status, Status::Error);
+        mock.assert_hits(1);
+
+        let round_tripped: models::RetrievedMessage =
+            serde_json::from_value(serde_json::to_value(message).unwrap()).unwrap();
+        assert_eq!(&round_tripped, message);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_idempotency_key_is_sent_once() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("POST").path("/send-message");
+            then.status(202)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "accountEmail": "support@sendblue.co",
+                    "content": "Hello, world!",
+                    "is_outbound": true,
+                    "status": "QUEUED",
+                    "message_handle": "dfa8c5b5-8a5c-4a1b-9d4a-5f9f7a7a8b2c",
+                    "date_sent": "2020-04-17T22:49:34.166Z",
+                    "date_updated": "2020-04-17T22:49:34.166Z",
+                    "from_number": "+16468528190",
+                    "number": "+14155552671",
+                    "to_number": "+14155552671",
+                    "media_url": "",
+                    "opted_out": false
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let message = || {
+            MessageBuilder::new("+14155552671")
+                .content("Hello, world!".into())
+                .idempotency_key("order-1234".into())
+                .build()
+                .unwrap()
+        };
+
+        let first = client.send(&message()).await.unwrap();
+        let second = client.send(&message()).await.unwrap();
+        assert_eq!(first, second);
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_sends_with_idempotency_key_are_sent_once() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("POST").path("/send-message");
+            then.status(202)
+                .delay(Duration::from_millis(100))
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "accountEmail": "support@sendblue.co",
+                    "content": "Hello, world!",
+                    "is_outbound": true,
+                    "status": "QUEUED",
+                    "message_handle": "dfa8c5b5-8a5c-4a1b-9d4a-5f9f7a7a8b2c",
+                    "date_sent": "2020-04-17T22:49:34.166Z",
+                    "date_updated": "2020-04-17T22:49:34.166Z",
+                    "from_number": "+16468528190",
+                    "number": "+14155552671",
+                    "to_number": "+14155552671",
+                    "media_url": "",
+                    "opted_out": false
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .idempotency_key("order-1234".into())
+            .build()
+            .unwrap();
+
+        let (first, second) = tokio::join!(client.send(&message), client.send(&message));
+        assert_eq!(first.unwrap(), second.unwrap());
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_meta_returns_headers() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("POST").path("/send-message");
+            then.status(202)
+                .header("content-type", "application/json")
+                .header("x-ratelimit-remaining", "41")
+                .json_body(json!({
+                    "accountEmail": "support@sendblue.co",
+                    "content": "Hello world!",
+                    "is_outbound": true,
+                    "status": "QUEUED",
+                    "error_code": null,
+                    "error_message": null,
+                    "message_handle": "dfa8c5b5-8a5c-4a1b-9d4a-5f9f7a7a8b2c",
+                    "date_sent": "2020-04-17T22:49:34.166Z",
+                    "date_updated": "2020-04-17T22:49:34.166Z",
+                    "from_number": "+16468528190",
+                    "number": "+16468528190",
+                    "to_number": "+16468528190",
+                    "was_downgraded": null,
+                    "plan": "blue",
+                    "media_url": "https://picsum.photos/200/300.jpg",
+                    "message_type": "message",
+                    "group_id": "",
+                    "participants": [],
+                    "send_style": "invisible",
+                    "opted_out": false,
+                    "error_detail": null
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .build()
+            .unwrap();
+
+        let (_, meta) = client.send_with_meta(&message).await.unwrap();
+        assert_eq!(meta.status, StatusCode::ACCEPTED);
+        assert_eq!(meta.rate_limit_remaining(), Some(41));
+        mock.assert_hits(1);
+    }
+
+    #[test]
+    fn test_preview_returns_url_and_body() {
+        let client = create_client_with_mock_url("http://localhost");
+        let message = MessageBuilder::<GroupMessage>::new_group()
+            .numbers(["+14155552671", "+14155552672"])
+            .content("Hello, group!".into())
+            .build()
+            .unwrap();
+
+        let (url, body) = client.preview(&message).unwrap();
+        assert_eq!(url, "http://localhost/send-group-message");
+        assert_eq!(
+            body,
+            json!({
+                "numbers": ["+14155552671", "+14155552672"],
+                "content": "Hello, group!",
+                "group_id": null
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decode_error_keeps_source() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("GET").path("/evaluate-service");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("{\"number\": 42}");
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let evaluate_service = EvaluateServiceBuilder::new()
+            .number("+14155552671".parse().unwrap())
+            .build();
+
+        let error = client
+            .evaluate_service(&evaluate_service)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SendblueError::Decode(_)));
+        let source = std::error::Error::source(&error).unwrap();
+        assert!(source.downcast_ref::<serde_json::Error>().is_some());
+        mock.assert_hits(1);
+    }
+
+    #[cfg(feature = "strict")]
+    #[test]
+    fn test_strict_rejects_unknown_fields() {
+        let result = serde_json::from_value::<models::TypingIndicatorResponse>(json!({
+            "number": "+14155552671",
+            "status": "SENT",
+            "error_message": null,
+            "new_field": true
+        }));
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("unknown field `new_field`"));
+    }
+
+    #[cfg(feature = "upload")]
+    #[tokio::test]
+    async fn test_upload_media_success() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("POST")
+                .path("/upload-file")
+                .header_exists("content-type")
+                .body_contains("filename=\"upload.png\"")
+                .body_contains("image/png");
+            then.status(201)
+                .header("content-type", "application/json")
+                .json_body(json!({ "media_url": "https://storage.sendblue.co/upload.png" }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let media_url = client
+            .upload_media(bytes::Bytes::from_static(b"\x89PNG"), "image/png")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            media_url,
+            MediaUrl::new("https://storage.sendblue.co/upload.png").unwrap()
+        );
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_account_success() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("GET")
+                .path("/account")
+                .header("sb-api-key-id", "test_key")
+                .header("sb-api-secret-key", "test_secret");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "email": "support@sendblue.co",
+                    "plan": "dedicated",
+                    "numbers": ["+16468528190", "+12125551234"],
+                    "usage": { "messages_sent": 40, "messages_limit": 100 }
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let account = client.account().await.unwrap();
+
+        assert_eq!(account.plan.as_deref(), Some("dedicated"));
+        assert_eq!(
+            account.numbers,
+            [
+                "+16468528190".parse().unwrap(),
+                "+12125551234".parse().unwrap()
+            ]
+        );
+        assert_eq!(account.usage.unwrap().messages_remaining(), Some(60));
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_health_check() {
+        let mock_server = MockServer::start();
+        let mut mock = mock_server.mock(|when, then| {
+            when.method("GET")
+                .path("/accounts/messages")
+                .query_param("limit", "1")
+                .header("sb-api-key-id", "test_key");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({ "messages": [] }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        client.health_check().await.unwrap();
+        mock.assert_hits(1);
+        mock.delete();
+
+        mock_server.mock(|when, then| {
+            when.method("GET").path("/accounts/messages");
+            then.status(401).body("Invalid API key");
+        });
+        assert!(matches!(
+            client.health_check().await,
+            Err(SendblueError::Unauthorized { body, .. }) if body == "Invalid API key"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_and_forbidden_responses() {
+        for status in [401, 403] {
+            let mock_server = MockServer::start();
+            let mock = mock_server.mock(|when, then| {
+                when.method("POST").path("/send-message");
+                then.status(status).body("Invalid API credentials");
+            });
+
+            let client = create_client_with_mock_url(&mock_server.base_url());
+            let message = MessageBuilder::new("+14155552671")
+                .content("Hello, world!".into())
+                .build()
+                .unwrap();
+
+            let error = client.send(&message).await.unwrap_err();
+            assert!(matches!(
+                &error,
+                SendblueError::Unauthorized { body, .. } if body == "Invalid API credentials"
+            ));
+            assert_eq!(error.status(), Some(status));
+            mock.assert_hits(1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_not_found_response() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("GET").path("/account");
+            then.status(404).body("Not found");
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let error = client.account().await.unwrap_err();
+        assert!(matches!(&error, SendblueError::NotFound { body, .. } if body == "Not found"));
+        assert_eq!(error.status(), Some(404));
+        assert!(!error.is_transient());
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_response_too_large() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method("GET").path("/account");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({ "email": "support@sendblue.co", "numbers": [] }));
+        });
+
+        let client = SendblueClient::builder("test_key".into(), "test_secret".into())
+            .base_url(mock_server.base_url())
+            .allow_http(true)
+            .max_response_size(16)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            client.account().await,
+            Err(SendblueError::ResponseTooLarge { limit: 16 })
+        ));
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        assert!(client.account().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_spaces_requests() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("GET").path("/account");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({ "email": "support@sendblue.co", "numbers": [] }));
+        });
+
+        let client = SendblueClient::builder("test_key".into(), "test_secret".into())
+            .base_url(mock_server.base_url())
+            .allow_http(true)
+            .rate_limit(10)
+            .build()
+            .unwrap();
+
+        // The first 10 requests use up the burst, the other 2 wait for a refill
+        let start = Instant::now();
+        for _ in 0..12 {
+            client.account().await.unwrap();
+        }
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(150), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(1), "{:?}", elapsed);
+        mock.assert_hits(12);
+    }
+
+    #[tokio::test]
+    async fn test_request_raw() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("PUT")
+                .path("/beta/feature")
+                .header("sb-api-key-id", "test_key")
+                .header("sb-api-secret-key", "test_secret")
+                .json_body(json!({ "enabled": true }));
+            then.status(418).body("I'm a teapot");
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let response = client
+            .request_raw(
+                reqwest::Method::PUT,
+                "/beta/feature",
+                Some(json!({ "enabled": true })),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 418);
+        assert_eq!(response.text().await.unwrap(), "I'm a teapot");
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_scheduled_success() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("POST")
+                .path("/cancel-scheduled-message")
+                .json_body(json!({ "message_handle": "dfd747ba-5600-4a8a" }));
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({ "status": "OK" }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        client
+            .cancel_scheduled(&"dfd747ba-5600-4a8a".into())
+            .await
+            .unwrap();
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_scheduled_not_found() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("POST").path("/cancel-scheduled-message");
+            then.status(404).body("message not found");
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let error = client
+            .cancel_scheduled(&"unknown".into())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SendblueError::NotFound { .. }));
+        mock.assert_hits(1);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_messages_iter_fetches_all_pages() {
+        let mock_server = MockServer::start();
+        let message = |uuid: &str| {
+            json!({
+                "date": "2023-09-21T20:22:05.066Z",
+                "type": "message",
+                "uuid": uuid,
+                "is_outbound": true,
+                "accountEmail": "youremail@gmail.com",
+                "status": "DELIVERED"
+            })
+        };
+        let first_page = mock_server.mock(|when, then| {
+            when.method("GET")
+                .path("/accounts/messages")
+                .query_param("limit", "2")
+                .query_param("offset", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({ "messages": [message("1"), message("2")] }));
+        });
+        let second_page = mock_server.mock(|when, then| {
+            when.method("GET")
+                .path("/accounts/messages")
+                .query_param("limit", "2")
+                .query_param("offset", "2");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({ "messages": [message("3")] }));
+        });
+
+        let client =
+            blocking::BlockingClient::from(create_client_with_mock_url(&mock_server.base_url()));
+        let params = GetMessagesParamsBuilder::new()
+            .limit(Some(2))
+            .offset(Some(0))
+            .build();
+
+        let uuids: Vec<String> = client
+            .messages_iter(params)
+            .map(|message| message.unwrap().uuid)
+            .collect();
+        assert_eq!(uuids, ["1", "2", "3"]);
+        first_page.assert_hits(1);
+        second_page.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_sends_number_as_e164() {
+        let mock_server = MockServer::start();
+        // A literal `+` would decode to a space, so this only matches `number=%2B14155552671`
+        let mock = mock_server.mock(|when, then| {
+            when.method("GET")
+                .path("/accounts/messages")
+                .query_param("number", "+14155552671");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({ "messages": [] }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let params = GetMessagesParamsBuilder::new()
+            .number(Some("+1 415-555-2671".into()))
+            .build();
+
+        let response = client.get_messages(params).await.unwrap();
+        assert!(response.is_empty());
+        mock.assert_hits(1);
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn test_axum_status_callback_extractor() {
+        use ::axum::{
+            body::Body,
+            extract::{FromRequest, Request},
+            http::StatusCode,
+            response::IntoResponse,
+        };
+
+        let payload = json!({
+            "accountEmail": "support@sendblue.co",
+            "content": "Hello, world!",
+            "is_outbound": true,
+            "status": "DELIVERED",
+            "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+            "date_sent": "2020-04-17T22:49:34.166Z",
+            "date_updated": "2020-04-17T22:49:35.000Z",
+            "from_number": "+16468528190",
+            "number": "+14155552671",
+            "to_number": "+14155552671",
+            "opted_out": false
+        });
+        let request = Request::new(Body::from(payload.to_string()));
+        let callback = axum::StatusCallback::from_request(request, &())
+            .await
+            .unwrap();
+        assert_eq!(callback.status, Status::Delivered);
+        assert_eq!(
+            callback.message_handle.as_str(),
+            "dfd747ba-5600-4a8a-804a-a614a0fbc1c5"
+        );
+
+        let request = Request::new(Body::from("not json"));
+        let rejection = axum::StatusCallback::from_request(request, &())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            rejection.into_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    #[cfg(feature = "middleware")]
+    #[tokio::test]
+    async fn test_middleware_runs_on_every_request() {
+        struct TagRequests;
+
+        #[async_trait::async_trait]
+        impl reqwest_middleware::Middleware for TagRequests {
+            async fn handle(
+                &self,
+                mut req: reqwest::Request,
+                extensions: &mut http::Extensions,
+                next: reqwest_middleware::Next<'_>,
+            ) -> reqwest_middleware::Result<Response> {
+                req.headers_mut()
+                    .insert("x-middleware", HeaderValue::from_static("tagged"));
+                next.run(req, extensions).await
+            }
+        }
+
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("GET")
+                .path("/accounts/messages")
+                .header("x-middleware", "tagged");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({ "messages": [] }));
+        });
+
+        let client = SendblueClientBuilder::new("test_key".into(), "test_secret".into())
+            .base_url(mock_server.base_url())
+            .allow_http(true)
+            .middleware(TagRequests)
+            .build()
+            .unwrap();
+
+        client
+            .get_messages(GetMessagesParamsBuilder::new().build())
+            .await
+            .unwrap();
+        mock.assert_hits(1);
+    }
+
+    #[cfg(feature = "middleware")]
+    #[test]
+    fn test_middleware_error_keeps_its_source() {
+        use std::error::Error;
+
+        let error = SendblueError::from(reqwest_middleware::Error::middleware(
+            std::io::Error::other("quota exceeded"),
+        ));
+        assert!(matches!(error, SendblueError::Middleware(_)));
+        assert!(error.source().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_by_contact_id() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("GET")
+                .path("/accounts/messages")
+                .query_param("cid", "contact_1234");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "messages": [
+                        {
+                            "date": "2023-09-21T20:22:05.066Z",
+                            "type": "message",
+                            "uuid": "595578e5-6701-4b89-ac9b-28cbfe99cd",
+                            "is_outbound": true,
+                            "accountEmail": "youremail@gmail.com",
+                            "status": "DELIVERED",
+                            "cid": "contact_1234"
+                        }
+                    ]
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let params = GetMessagesParamsBuilder::new()
+            .cid(Some(models::ContactId::from("contact_1234")))
+            .build();
+
+        let response = client.get_messages(params).await.unwrap();
+        assert_eq!(response[0].cid, Some("contact_1234".into()));
+        assert_eq!(
+            response[0].cid.as_ref().unwrap().to_string(),
+            "contact_1234"
+        );
+        mock.assert_hits(1);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_messages_iter_follows_cursor() {
+        let mock_server = MockServer::start();
+        let message = |uuid: &str| {
+            json!({
+                "date": "2023-09-21T20:22:05.066Z",
+                "type": "message",
+                "uuid": uuid,
+                "is_outbound": true,
+                "accountEmail": "youremail@gmail.com",
+                "status": "DELIVERED"
+            })
+        };
+        // Defined first so it takes precedence, the cursor request also carries `limit=2`
+        let second_page = mock_server.mock(|when, then| {
+            when.method("GET")
+                .path("/accounts/messages")
+                .query_param("cursor", "page-2");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({ "messages": [message("3")] }));
+        });
+        let first_page = mock_server.mock(|when, then| {
+            when.method("GET")
+                .path("/accounts/messages")
+                .query_param("limit", "2");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "messages": [message("1"), message("2")],
+                    "next_cursor": "page-2"
+                }));
+        });
+
+        let client =
+            blocking::BlockingClient::from(create_client_with_mock_url(&mock_server.base_url()));
+        let params = GetMessagesParamsBuilder::new().limit(Some(2)).build();
+
+        let uuids: Vec<String> = client
+            .messages_iter(params)
+            .map(|message| message.unwrap().uuid)
+            .collect();
+        assert_eq!(uuids, ["1", "2", "3"]);
+        first_page.assert_hits(1);
+        second_page.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_validate_media() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method("HEAD").path("/small.png");
+            then.status(200).header("content-length", "1024");
+        });
+        mock_server.mock(|when, then| {
+            when.method("HEAD").path("/large.mp4");
+            then.status(200).header("content-length", "200000000");
+        });
+        mock_server.mock(|when, then| {
+            when.method("HEAD").path("/missing.png");
+            then.status(404);
+        });
+        mock_server.mock(|when, then| {
+            when.method("HEAD").path("/unknown-size.png");
+            then.status(200);
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let media_url = |path: &str| MediaUrl::new(&mock_server.url(path)).unwrap();
+
+        client
+            .validate_media(&media_url("/small.png"))
+            .await
+            .unwrap();
+        assert!(matches!(
+            client.validate_media(&media_url("/large.mp4")).await,
+            Err(SendblueError::MediaTooLarge {
+                size: 200_000_000,
+                ..
+            })
+        ));
+        let error = client
+            .validate_media(&media_url("/missing.png"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            SendblueError::MediaUnreachable { status: 404 }
+        ));
+        assert_eq!(error.status(), Some(404));
+        client
+            .validate_media(&media_url("/unknown-size.png"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_process_response_created_and_no_content() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.path("/created");
+            then.status(201)
+                .header("content-type", "application/json")
+                .json_body(json!({ "id": 1 }));
+        });
+        mock_server.mock(|when, then| {
+            when.path("/no-content");
+            then.status(204);
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+
+        let response = client
+            .client
+            .get(mock_server.url("/created"))
+            .send()
+            .await
+            .unwrap();
+        let created: serde_json::Value = client.process_response(response).await.unwrap();
+        assert_eq!(created, json!({ "id": 1 }));
+
+        let response = client
+            .client
+            .get(mock_server.url("/no-content"))
+            .send()
+            .await
+            .unwrap();
+        let _: () = client.process_response(response).await.unwrap();
+    }
+
+    #[test]
+    fn test_get_messages_response_has_more() {
+        let response: GetMessagesResponse = serde_json::from_value(json!({
+            "messages": [
+                {
+                    "date": "2023-09-21T20:22:05.066Z",
+                    "type": "message",
+                    "uuid": "dfa8c5b5-8a5c-4a1b-9d4a-5f9f7a7a8b2c",
+                    "is_outbound": true,
+                    "accountEmail": "support@sendblue.co",
+                    "was_downgraded": false,
+                    "status": "DELIVERED"
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert!(response.has_more(1));
+        assert!(!response.has_more(2));
+        assert!(!response.has_more(0));
+    }
+
+    #[test]
+    fn test_message_response_into_retrieved_message() {
+        let response: models::MessageResponse = serde_json::from_value(json!({
+            "accountEmail": "support@sendblue.co",
+            "content": "Hello, world!",
+            "is_outbound": true,
+            "status": "QUEUED",
+            "error_code": null,
+            "message_handle": "dfa8c5b5-8a5c-4a1b-9d4a-5f9f7a7a8b2c",
+            "date_sent": "2020-04-17T22:49:34.166Z",
+            "date_updated": "2020-04-17T22:49:35.000Z",
+            "from_number": "+16468528190",
+            "number": "+14155552671",
+            "to_number": "+14155552671",
+            "media_url": "",
+            "send_style": "invisible",
+            "opted_out": false
+        }))
+        .unwrap();
+
+        let message = models::RetrievedMessage::from(response);
+        assert_eq!(message.uuid, "dfa8c5b5-8a5c-4a1b-9d4a-5f9f7a7a8b2c");
+        assert_eq!(message.date, "2020-04-17T22:49:34.166Z");
+        assert_eq!(message.content.as_deref(), Some("Hello, world!"));
+        assert_eq!(message.media_url, None);
+        assert_eq!(message.send_style.as_deref(), Some("invisible"));
+        assert_eq!(message.message_type, models::MessageType::Message);
+        assert_eq!(message.status, Status::Queued);
+        assert!(message.reply_builder().is_some());
+    }
+
+    #[test]
+    fn test_retrieved_messages_compare_and_hash() {
+        let message = json!({
+            "date": "2023-09-21T20:22:05.066Z",
+            "type": "message",
+            "uuid": "1",
+            "is_outbound": true,
+            "accountEmail": "youremail@gmail.com",
+            "status": "DELIVERED"
+        });
+        let first: models::RetrievedMessage = serde_json::from_value(message.clone()).unwrap();
+        let second: models::RetrievedMessage = serde_json::from_value(message).unwrap();
+        assert_eq!(first, second);
+
+        let unique: std::collections::HashSet<_> = [first, second].into_iter().collect();
+        assert_eq!(unique.len(), 1);
+    }
+
+    #[test]
+    fn test_group_by_number() {
+        let message = |uuid: &str, is_outbound: bool, number: Option<&str>| {
+            let number_field = if is_outbound {
+                "to_number"
+            } else {
+                "from_number"
+            };
+            let mut message = json!({
+                "date": "2023-09-21T20:22:05.066Z",
+                "type": "message",
+                "uuid": uuid,
+                "is_outbound": is_outbound,
+                "accountEmail": "youremail@gmail.com",
+                "status": "DELIVERED"
+            });
+            message[number_field] = json!(number);
+            message
+        };
+        let response: models::GetMessagesResponse = serde_json::from_value(json!({
+            "messages": [
+                message("1", true, Some("+14155552671")),
+                message("2", false, Some("+14155552671")),
+                message("3", false, Some("+12125551234")),
+                message("4", false, None)
+            ]
+        }))
+        .unwrap();
+
+        let groups = response.group_by_number();
+        assert_eq!(groups.len(), 2);
+        let uuids: Vec<&str> = groups[&models::PhoneNumber::new("+14155552671").unwrap()]
+            .iter()
+            .map(|m| m.uuid.as_str())
+            .collect();
+        assert_eq!(uuids, ["1", "2"]);
+        assert_eq!(
+            groups[&models::PhoneNumber::new("+12125551234").unwrap()].len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_filters_by_status() {
+        let mock_server = MockServer::start();
+        let message = |uuid: &str, status: &str| {
+            json!({
+                "date": "2023-09-21T20:22:05.066Z",
+                "type": "message",
+                "uuid": uuid,
+                "is_outbound": true,
+                "accountEmail": "youremail@gmail.com",
+                "status": status
+            })
+        };
+        let mock = mock_server.mock(|when, then| {
+            when.method("GET").path("/accounts/messages");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "messages": [
+                        message("1", "DELIVERED"),
+                        message("2", "SENT"),
+                        message("3", "DELIVERED")
+                    ]
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let params = GetMessagesParamsBuilder::new()
+            .status(Some(Status::Delivered))
+            .build();
+
+        let response = client.get_messages(params).await.unwrap();
+        assert_eq!(response.len(), 2);
+        assert!(response.iter().all(|m| m.status == Status::Delivered));
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_send_group_message_success() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("POST")
+                .path("/send-group-message")
+                .header("sb-api-key-id", "test_key")
+                .header("sb-api-secret-key", "test_secret");
+            then.status(202)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                  "accountEmail": "YOUR EMAIL",
+                  "content": "Hello world",
+                  "is_outbound": true,
+                  "status": "QUEUED",
+                  "error_code": null,
+                  "error_message": null,
+                  "message_handle": "073c1408-a6d9-48e2-ae8c-01f06443833",
+                  "date_sent": "2021-05-19T23:07:23.371Z",
+                  "date_updated": "2021-05-19T23:07:23.371Z",
+                  "from_number": "+16468528190",
+                  "number": ["+11112223333", "+13332221111"],
+                  "to_number": ["+11112223333", "+13332221111"],
+                  "was_downgraded": null,
+                  "plan": "blue",
+                  "media_url": "https://picsum.photos/200/300.jpg",
+                  "message_type": "group",
+                  "group_id": "66e3b90d-4447-43c6-9439-15a69408ac2"
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let group_message = MessageBuilder::<GroupMessage>::new_group()
+            .numbers(["+14155552671", "+14155552672"])
+            .content("Test group message".into())
+            .build()
+            .unwrap();
+
+        let result = client.send(&group_message).await;
+        if let Err(e) = &result {
+            error!("Error in test_send_group_message_success: {:?}", e);
+        }
+        assert!(result.is_ok());
+        let response = result.unwrap();
+        assert_eq!(response.status, Status::Queued);
+        assert_eq!(
+            response.message_handle.as_str(),
+            "073c1408-a6d9-48e2-ae8c-01f06443833"
+        );
+        assert_eq!(response.message_type, models::MessageType::Group);
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_send_to_group_addresses_group_by_id() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("POST")
+                .path("/send-group-message")
+                .json_body_partial(
+                    json!({
+                        "group_id": "66e3b90d-4447-43c6-9439-15a69408ac2",
+                        "content": "See you all at 7"
+                    })
+                    .to_string(),
+                );
+            then.status(202)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                  "accountEmail": "YOUR EMAIL",
+                  "content": "See you all at 7",
+                  "is_outbound": true,
+                  "status": "QUEUED",
+                  "message_handle": "073c1408-a6d9-48e2-ae8c-01f06443833",
+                  "date_sent": "2021-05-19T23:07:23.371Z",
+                  "date_updated": "2021-05-19T23:07:23.371Z",
+                  "from_number": "+16468528190",
+                  "number": ["+14155552671", "+12125551234"],
+                  "to_number": ["+14155552671", "+12125551234"],
+                  "plan": "blue",
+                  "message_type": "group",
+                  "group_id": "66e3b90d-4447-43c6-9439-15a69408ac2"
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let group_id = models::GroupId::from("66e3b90d-4447-43c6-9439-15a69408ac2");
+
+        let response = client
+            .send_to_group(&group_id, "See you all at 7")
+            .await
+            .unwrap();
+        assert_eq!(response.group_id, group_id);
+        mock.assert_hits(1);
+    }
+
+    #[test]
+    fn test_message_type_unknown_round_trips() {
+        let message_type: models::MessageType = serde_json::from_value(json!("reaction")).unwrap();
+        assert_eq!(
+            message_type,
+            models::MessageType::Unknown("reaction".into())
+        );
+        assert_eq!(
+            serde_json::to_value(&message_type).unwrap(),
+            json!("reaction")
+        );
+        assert_eq!(message_type.to_string(), "reaction");
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_service_batch_preserves_order() {
+        let mock_server = MockServer::start();
+        let numbers = ["+14155552671", "+12125551234", "+12025550123"];
+        for (i, number) in numbers.iter().enumerate() {
+            mock_server.mock(|when, then| {
+                when.method("GET")
+                    .path("/evaluate-service")
+                    .query_param("number", *number);
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .delay(Duration::from_millis(50 * (numbers.len() - i) as u64))
+                    .json_body(json!({ "number": number, "service": "iMessage" }));
+            });
+        }
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let parsed = numbers.map(|n| n.parse().unwrap());
+        let results = client.evaluate_service_batch(&parsed, 2).await;
+
+        let evaluated: Vec<_> = results.into_iter().map(|r| r.unwrap().number).collect();
+        assert_eq!(evaluated, numbers);
+    }
+
+    #[tokio::test]
+    async fn test_segment_contacts() {
+        let mock_server = MockServer::start();
+        for (number, service) in [("+14155552671", "iMessage"), ("+12125551234", "SMS")] {
+            mock_server.mock(|when, then| {
+                when.method("GET")
+                    .path("/evaluate-service")
+                    .query_param("number", number);
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .json_body(json!({ "number": number, "service": service }));
+            });
+        }
+        mock_server.mock(|when, then| {
+            when.method("GET")
+                .path("/evaluate-service")
+                .query_param("number", "+12025550123");
+            then.status(400).body("Invalid number");
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let numbers = ["+14155552671", "+12125551234", "+12025550123"]
+            .map(|n| n.parse().unwrap())
+            .to_vec();
+        let segments = client.segment_contacts(numbers.clone()).await.unwrap();
+
+        assert_eq!(segments.imessage, [numbers[0].clone()]);
+        assert_eq!(segments.sms, [numbers[1].clone()]);
+        assert_eq!(segments.errored.len(), 1);
+        assert_eq!(segments.errored[0].0, numbers[2]);
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_service_success() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("GET")
+                .path("/evaluate-service")
+                .header("sb-api-key-id", "test_key")
+                .header("sb-api-secret-key", "test_secret");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "number": "+14155552671",
+                    "service": "iMessage"
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let phone_number: models::PhoneNumber = "+14155552671".parse().unwrap();
+        let evaluate_service = EvaluateServiceBuilder::new().number(phone_number).build();
+
+        let result = client.evaluate_service(&evaluate_service).await;
+        if let Err(e) = &result {
+            error!("Error in test_evaluate_service_success: {:?}", e);
+        }
+        assert!(result.is_ok());
+        let response = result.unwrap();
+        assert_eq!(response.number, "+14155552671");
+        assert_eq!(response.service, models::EvaluateServiceType::IMessage);
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_send_typing_indicator_success() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("POST")
+                .path("/send-typing-indicator")
+                .header("sb-api-key-id", "test_key")
+                .header("sb-api-secret-key", "test_secret")
+                .json_body(json!({ "number": "+14155552671" }));
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "number": "+14155552671",
+                    "status": "SENT"
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let phone_number: models::PhoneNumber = "+14155552671".parse().unwrap();
+
+        let result = client.send_typing_indicator(&phone_number).await;
+        if let Err(e) = &result {
+            error!("Error in test_send_typing_indicator_success: {:?}", e);
+        }
+        assert!(result.is_ok());
+        let response = result.unwrap();
+        assert_eq!(response.status, TypingIndicatorStatus::Sent);
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_send_typing_indicator_failure() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("POST")
+                .path("/send-typing-indicator")
+                .header("sb-api-key-id", "test_key")
+                .header("sb-api-secret-key", "test_secret");
+            then.status(400)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "status": "ERROR",
+                    "error_message": "Failed to send typing indicator"
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let phone_number: models::PhoneNumber = "+14155552671".parse().unwrap();
+
+        let result = client.send_typing_indicator(&phone_number).await;
+        if let Err(e) = &result {
+            error!("Error in test_send_typing_indicator_failure: {:?}", e);
+        }
+        assert!(result.is_err());
+        let response = result.unwrap_err();
+        if let SendblueError::BadRequest { body, .. } = response {
+            let expected_error = json!({
+                "status": "ERROR",
+                "error_message": "Failed to send typing indicator"
+            });
+            let actual_error: serde_json::Value = serde_json::from_str(&body).unwrap();
+            assert_eq!(actual_error, expected_error);
+        }
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_error_classification_connect() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let client = create_client_with_mock_url(&format!("http://127.0.0.1:{}", port));
+
+        let error = client.account().await.unwrap_err();
+        assert!(error.is_connect());
+        assert!(!error.is_timeout());
+        assert!(error.is_transient());
+    }
+
+    #[tokio::test]
+    async fn test_unhandled_status_keeps_status_code() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method("GET").path("/account");
+            then.status(503).body("Service Unavailable");
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let error = client.account().await.unwrap_err();
+
+        match &error {
+            SendblueError::Http { status, body, .. } => {
+                assert_eq!(*status, 503);
+                assert_eq!(body, "Service Unavailable");
+            }
+            other => panic!("expected an HTTP error, got {:?}", other),
+        }
+        assert_eq!(error.status(), Some(503));
+        assert!(error.is_transient());
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_surfaced() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method("POST").path("/send-message");
+            then.status(202)
+                .header("content-type", "application/json")
+                .header("x-request-id", "req_1234")
+                .json_body(json!({
+                    "accountEmail": "YOUR EMAIL",
+                    "content": "Hello, world!",
+                    "is_outbound": true,
+                    "status": "QUEUED",
+                    "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+                    "date_sent": "2020-04-17T22:49:34.166Z",
+                    "date_updated": "2020-04-17T22:49:34.166Z",
+                    "from_number": "+16468528190",
+                    "number": "+14155552671",
+                    "to_number": "+14155552671",
+                    "was_downgraded": null,
+                    "plan": "blue",
+                    "opted_out": false
+                }));
+        });
+        mock_server.mock(|when, then| {
+            when.method("GET").path("/account");
+            then.status(502)
+                .header("x-request-id", "req_5678")
+                .body("Bad Gateway");
+        });
+        mock_server.mock(|when, then| {
+            when.method("POST").path("/send-group-message");
+            then.status(400)
+                .header("x-request-id", "req_9012")
+                .body("Invalid group");
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .build()
+            .unwrap();
+
+        let (_, meta) = client.send_with_meta(&message).await.unwrap();
+        assert_eq!(meta.request_id(), Some("req_1234"));
+
+        let error = client.account().await.unwrap_err();
+        assert_eq!(error.request_id(), Some("req_5678"));
+
+        let error = client
+            .send_to_group(
+                &"66e3b90d-4447-43c6-9439-15a69408ac2".into(),
+                "Hello, group!",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SendblueError::BadRequest { .. }));
+        assert_eq!(error.request_id(), Some("req_9012"));
+        assert_eq!(error.status(), Some(400));
+    }
+
+    #[tokio::test]
+    async fn test_error_classification_timeout_and_status() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.path("/slow");
+            then.status(200).delay(Duration::from_millis(500));
+        });
+        mock_server.mock(|when, then| {
+            when.path("/unavailable");
+            then.status(503);
+        });
+        mock_server.mock(|when, then| {
+            when.path("/bad");
+            then.status(400);
+        });
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
 
-    fn create_client_with_mock_url(base_url: &str) -> SendblueClient {
-        SendblueClient::new_with_url("test_key".into(), "test_secret".into(), base_url.into())
+        let timeout =
+            SendblueError::from(http.get(mock_server.url("/slow")).send().await.unwrap_err());
+        assert!(timeout.is_timeout());
+        assert!(!timeout.is_connect());
+        assert!(timeout.is_transient());
+
+        let unavailable = http
+            .get(mock_server.url("/unavailable"))
+            .send()
+            .await
+            .unwrap();
+        let unavailable = SendblueError::from(unavailable.error_for_status().unwrap_err());
+        assert!(unavailable.is_transient());
+
+        let bad = http.get(mock_server.url("/bad")).send().await.unwrap();
+        let bad = SendblueError::from(bad.error_for_status().unwrap_err());
+        assert!(!bad.is_transient());
+
+        assert!(SendblueError::DeadlineExceeded.is_timeout());
+        assert!(!SendblueError::BadRequest {
+            body: "Invalid request".into(),
+            request_id: None,
+            request_body: None,
+        }
+        .is_transient());
     }
 
     #[tokio::test]
-    async fn test_send_message_success() {
+    async fn test_client_from_config() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("GET").path("/account");
+            then.status(200)
+                .header("content-type", "application/json")
+                .delay(Duration::from_millis(500))
+                .json_body(json!({ "email": "support@sendblue.co", "numbers": [] }));
+        });
+        let config: SendblueConfig = serde_json::from_value(json!({
+            "api_key": "test_key",
+            "api_secret": "test_secret",
+            "base_url": mock_server.base_url(),
+            "timeout_ms": 50,
+            "allow_http": true
+        }))
+        .unwrap();
+        assert!(!format!("{:?}", config).contains("test_secret"));
+
+        let client = SendblueClient::from_config(config).unwrap();
+        let error = client.account().await.unwrap_err();
+        assert!(error.is_timeout());
+        mock.assert_hits(1);
+    }
+
+    #[test]
+    fn test_config_maps_client_settings() {
+        let config: SendblueConfig = serde_json::from_value(json!({
+            "api_key": "test_key",
+            "api_secret": "test_secret",
+            "default_region": "US",
+            "treat_opt_out_as_error": true,
+            "attach_request_body": true,
+            "default_status_callback": "https://example.com/default",
+            "max_response_size": 1024,
+            "rate_limit": 5,
+            "send_concurrency": 2
+        }))
+        .unwrap();
+
+        let client = SendblueClient::from_config(config).unwrap();
+        assert_eq!(
+            client.parse_number("(415) 555-2671").unwrap().to_e164(),
+            "+14155552671"
+        );
+        assert!(client.treat_opt_out_as_error);
+        assert!(client.attach_request_body);
+        assert_eq!(client.max_response_size, 1024);
+        assert!(client.rate_limiter.is_some());
+        assert_eq!(client.send_concurrency, 2);
+
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .build()
+            .unwrap();
+        let (_, body) = client.preview(&message).unwrap();
+        assert_eq!(body["status_callback"], "https://example.com/default");
+    }
+
+    #[test]
+    fn test_config_pool_idle_timeout_can_be_disabled() {
+        let credentials = json!({ "api_key": "test_key", "api_secret": "test_secret" });
+        let config: SendblueConfig = serde_json::from_value(credentials.clone()).unwrap();
+        assert_eq!(config.pool_idle_timeout_ms, None);
+
+        let mut disabled = credentials.clone();
+        disabled["pool_idle_timeout_ms"] = json!(null);
+        let config: SendblueConfig = serde_json::from_value(disabled).unwrap();
+        assert_eq!(config.pool_idle_timeout_ms, Some(None));
+        assert!(SendblueClient::from_config(config).is_ok());
+
+        let mut timeout = credentials;
+        timeout["pool_idle_timeout_ms"] = json!(30000);
+        let config: SendblueConfig = serde_json::from_value(timeout).unwrap();
+        assert_eq!(config.pool_idle_timeout_ms, Some(Some(30000)));
+    }
+
+    #[tokio::test]
+    async fn test_send_message_failure() {
         let mock_server = MockServer::start();
         let mock = mock_server.mock(|when, then| {
             when.method("POST")
                 .path("/send-message")
                 .header("sb-api-key-id", "test_key")
                 .header("sb-api-secret-key", "test_secret");
-            then.status(200)
+            then.status(400)
                 .header("content-type", "application/json")
                 .json_body(json!({
-                    "accountEmail": "YOUR EMAIL",
-                    "content": "Hello world!",
+                    "status": "ERROR",
+                    "message": "Bad request"
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let message = MessageBuilder::new("+14155552671")
+            .content("Test message".into())
+            .build()
+            .unwrap();
+
+        let result = client.send(&message).await;
+        if let Err(e) = &result {
+            error!("Error in test_send_message_failure: {:?}", e);
+        }
+        assert!(result.is_err());
+        mock.assert_hits(1);
+    }
+
+    #[test]
+    fn test_group_message_single_media_url() {
+        let group_message = MessageBuilder::<GroupMessage>::new_group()
+            .group_id("group_id".into())
+            .media_url(MediaUrl::new("https://example.com/first.jpg").unwrap())
+            .build()
+            .unwrap();
+
+        let body = serde_json::to_value(&group_message).unwrap();
+        assert_eq!(body["media_url"], json!("https://example.com/first.jpg"));
+
+        let parsed: GroupMessage = serde_json::from_str(&body.to_string()).unwrap();
+        assert_eq!(parsed.media_urls, group_message.media_urls);
+    }
+
+    #[test]
+    fn test_group_message_multiple_media_urls() {
+        let group_message = MessageBuilder::<GroupMessage>::new_group()
+            .group_id("group_id".into())
+            .media_url(MediaUrl::new("https://example.com/first.jpg").unwrap())
+            .media_urls(vec![
+                MediaUrl::new("https://example.com/second.jpg").unwrap()
+            ])
+            .build()
+            .unwrap();
+
+        let body = serde_json::to_value(&group_message).unwrap();
+        assert_eq!(
+            body["media_url"],
+            json!([
+                "https://example.com/first.jpg",
+                "https://example.com/second.jpg"
+            ])
+        );
+
+        let parsed: GroupMessage = serde_json::from_str(&body.to_string()).unwrap();
+        assert_eq!(parsed.media_urls, group_message.media_urls);
+    }
+
+    #[test]
+    fn test_group_message_requires_content_or_media() {
+        let result = MessageBuilder::<GroupMessage>::new_group()
+            .group_id("group_id".into())
+            .media_urls(Vec::new())
+            .build();
+
+        assert!(matches!(result, Err(SendblueError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_message_content_and_media_combinations() {
+        let media_url = MediaUrl::new("https://example.com/image.png").unwrap();
+
+        let content_only = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .build();
+        assert!(content_only.is_ok());
+
+        let media_only = MessageBuilder::new("+14155552671")
+            .media_url(media_url.clone())
+            .build();
+        assert!(media_only.is_ok());
+
+        let both = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .media_url(media_url)
+            .build()
+            .unwrap();
+        assert!(both.content.is_some() && both.media_url.is_some());
+
+        let neither = MessageBuilder::new("+14155552671").build();
+        assert!(matches!(
+            neither,
+            Err(SendblueError::ValidationError(msg)) if msg == "Either content or media_url must be provided"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_send_reaction_success() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method("POST")
+                .path("/send-reaction")
+                .header("sb-api-key-id", "test_key")
+                .json_body(json!({
+                    "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+                    "reaction": "emphasize"
+                }));
+            then.status(202)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "accountEmail": "support@sendblue.co",
+                    "content": "",
                     "is_outbound": true,
                     "status": "QUEUED",
                     "error_code": null,
-                    "error_message": null,
+                    "message_handle": "07b8a4f2-25a5-4ec5-a7d4-26b5a1a3e8a3",
+                    "date_sent": "2020-04-17T22:49:34.166Z",
+                    "date_updated": "2020-04-17T22:49:34.166Z",
+                    "from_number": "+16468528190",
+                    "number": "+14155552671",
+                    "to_number": "+14155552671",
+                    "media_url": "",
+                    "opted_out": false
+                }));
+        });
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let response = client
+            .send_reaction(
+                &"dfd747ba-5600-4a8a-804a-a614a0fbc1c5".into(),
+                models::Reaction::Emphasize,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, Status::Queued);
+        mock.assert_hits(1);
+    }
+
+    #[test]
+    fn test_parse_number_with_default_region() {
+        let client = SendblueClient::builder("test_key".into(), "test_secret".into())
+            .default_region(phonenumber::country::US)
+            .build()
+            .unwrap();
+
+        for number in ["(415) 555-2671", "+14155552671"] {
+            let parsed = client.parse_number(number).unwrap();
+            assert_eq!(parsed, "+14155552671".parse().unwrap());
+        }
+
+        let client = SendblueClient::new("test_key".into(), "test_secret".into());
+        assert!(matches!(
+            client.parse_number("(415) 555-2671"),
+            Err(SendblueError::ValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_send_to_many_sends_individual_messages() {
+        let mock_server = MockServer::start();
+        let mocks: Vec<_> = ["+14155552671", "+12125551234"]
+            .into_iter()
+            .map(|number| {
+                mock_server.mock(|when, then| {
+                    when.method("POST")
+                        .path("/send-message")
+                        .json_body_partial(json!({ "number": number }).to_string());
+                    then.status(202)
+                        .header("content-type", "application/json")
+                        .json_body(json!({
+                            "accountEmail": "support@sendblue.co",
+                            "content": "Hello, world!",
+                            "is_outbound": true,
+                            "status": "QUEUED",
+                            "error_code": null,
+                            "message_handle": format!("handle-{}", number),
+                            "date_sent": "2020-04-17T22:49:34.166Z",
+                            "date_updated": "2020-04-17T22:49:34.166Z",
+                            "from_number": "+16468528190",
+                            "number": number,
+                            "to_number": number,
+                            "media_url": "",
+                            "opted_out": false
+                        }));
+                })
+            })
+            .collect();
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let numbers = ["+14155552671", "+12125551234"].map(|n| n.parse().unwrap());
+        let results = client.send_to_many(numbers, "Hello, world!").await;
+
+        let handles: Vec<_> = results
+            .into_iter()
+            .map(|result| result.unwrap().message_handle.to_string())
+            .collect();
+        assert_eq!(handles, ["handle-+14155552671", "handle-+12125551234"]);
+        for mock in mocks {
+            mock.assert_hits(1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_treat_opt_out_as_error() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method("POST").path("/send-message");
+            then.status(202)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "accountEmail": "support@sendblue.co",
+                    "content": "Hello, world!",
+                    "is_outbound": true,
+                    "status": "FAILED",
+                    "error_code": null,
+                    "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+                    "date_sent": "2020-04-17T22:49:34.166Z",
+                    "date_updated": "2020-04-17T22:49:34.166Z",
+                    "from_number": "+16468528190",
+                    "number": "+14155552671",
+                    "to_number": "+14155552671",
+                    "media_url": "",
+                    "opted_out": true
+                }));
+        });
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .build()
+            .unwrap();
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        assert!(client.send(&message).await.unwrap().opted_out);
+
+        let client = SendblueClient::builder("test_key".into(), "test_secret".into())
+            .base_url(mock_server.base_url())
+            .allow_http(true)
+            .treat_opt_out_as_error(true)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            client.send(&message).await,
+            Err(SendblueError::RecipientOptedOut { number }) if number == "+14155552671"
+        ));
+    }
+
+    #[test]
+    fn test_default_status_callback() {
+        let client = SendblueClient::builder("test_key".into(), "test_secret".into())
+            .default_status_callback(
+                models::CallbackUrl::new("https://example.com/default").unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .build()
+            .unwrap();
+        let (_, body) = client.preview(&message).unwrap();
+        assert_eq!(body["status_callback"], "https://example.com/default");
+
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .status_callback(models::CallbackUrl::new("https://example.com/explicit").unwrap())
+            .build()
+            .unwrap();
+        let (_, body) = client.preview(&message).unwrap();
+        assert_eq!(body["status_callback"], "https://example.com/explicit");
+    }
+
+    #[test]
+    fn test_request_models_clone() {
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .build()
+            .unwrap();
+        assert_eq!(message.clone(), message);
+
+        let group_message = MessageBuilder::new_group()
+            .numbers(["+14155552671", "+12125551234"])
+            .content("Hello, group!".into())
+            .build()
+            .unwrap();
+        assert_eq!(group_message.clone(), group_message);
+
+        let params = GetMessagesParamsBuilder::new().limit(Some(10)).build();
+        assert_eq!(params.clone(), params);
+    }
+
+    #[tokio::test]
+    async fn test_send_dyn_mixed_messages() {
+        let mock_server = MockServer::start();
+        let message_mock = mock_server.mock(|when, then| {
+            when.method("POST").path("/send-message");
+            then.status(202)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "accountEmail": "support@sendblue.co",
+                    "content": "Hello, world!",
+                    "is_outbound": true,
+                    "status": "QUEUED",
                     "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+                    "date_sent": "2020-04-17T22:49:34.166Z",
+                    "date_updated": "2020-04-17T22:49:34.166Z",
+                    "from_number": "+16468528190",
+                    "number": "+14155552671",
+                    "to_number": "+14155552671",
+                    "media_url": "",
+                    "opted_out": false
+                }));
+        });
+        let group_mock = mock_server.mock(|when, then| {
+            when.method("POST").path("/send-group-message");
+            then.status(202)
+                .header("content-type", "application/json")
+                .json_body(json!({
+                    "accountEmail": "support@sendblue.co",
+                    "content": "Hello, group!",
+                    "is_outbound": true,
+                    "status": "QUEUED",
+                    "error_code": null,
+                    "error_message": null,
+                    "message_handle": "073c1408-a6d9-48e2-ae8c-01f06443833",
                     "date_sent": "2023-09-27T16:35:32.287Z",
                     "date_updated": "2023-09-27T16:35:32.703Z",
                     "from_number": "+16468528190",
-                    "number": "+19998887777",
-                    "to_number": "+19998887777",
+                    "number": ["+14155552671", "+12125551234"],
+                    "to_number": ["+14155552671", "+12125551234"],
                     "was_downgraded": null,
                     "plan": "dedicated",
-                    "media_url": "https://picsum.photos/200/300.jpg",
-                    "message_type": "message",
-                    "group_id": "",
-                    "participants": [],
-                    "send_style": "invisible",
-                    "opted_out": false,
-                    "error_detail": null
+                    "media_url": "",
+                    "message_type": "group",
+                    "group_id": "66e3b90d-4447-43c6-9439-15a69408ac2"
                 }));
         });
 
-        let client = create_client_with_mock_url(&mock_server.base_url());
-        let phone_number = parse(None, "+10722971673").unwrap();
-        let message = MessageBuilder::new(phone_number.clone())
-            .content("Test message".into())
+        let queue: Vec<models::DynMessage> = vec![
+            MessageBuilder::new("+14155552671")
+                .content("Hello, world!".into())
+                .build()
+                .unwrap()
+                .into(),
+            MessageBuilder::new_group()
+                .numbers(["+14155552671", "+12125551234"])
+                .content("Hello, group!".into())
+                .build()
+                .unwrap()
+                .into(),
+        ];
+
+        let client = create_client_with_mock_url(&mock_server.base_url());
+        let mut responses = Vec::new();
+        for message in &queue {
+            responses.push(client.send_dyn(message).await.unwrap());
+        }
+
+        assert!(matches!(
+            responses[0],
+            models::DynMessageResponse::Message(_)
+        ));
+        assert!(matches!(responses[1], models::DynMessageResponse::Group(_)));
+        message_mock.assert_hits(1);
+        group_mock.assert_hits(1);
+    }
+
+    #[test]
+    fn test_reply_builder_from_message_response() {
+        let response: models::MessageResponse = serde_json::from_value(json!({
+            "accountEmail": "YOUR EMAIL",
+            "content": "Hello world!",
+            "is_outbound": true,
+            "status": "QUEUED",
+            "error_code": null,
+            "error_message": null,
+            "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+            "date_sent": "2023-09-27T16:35:32.287Z",
+            "date_updated": "2023-09-27T16:35:32.703Z",
+            "from_number": "+16468528190",
+            "number": "+16468528190",
+            "to_number": "+16468528190",
+            "was_downgraded": null,
+            "plan": "dedicated",
+            "media_url": "",
+            "message_type": "message",
+            "group_id": "",
+            "participants": [],
+            "send_style": "",
+            "opted_out": false,
+            "error_detail": null
+        }))
+        .unwrap();
+
+        let reply = response
+            .reply_builder()
+            .content("Reply".into())
+            .build()
+            .unwrap();
+
+        assert_eq!(reply.number, "+16468528190");
+        assert_eq!(reply.content.as_deref(), Some("Reply"));
+    }
+
+    #[test]
+    fn test_reply_builder_from_group_message_response() {
+        let response: models::GroupMessageResponse = serde_json::from_value(json!({
+            "accountEmail": "YOUR EMAIL",
+            "content": "Hello world",
+            "is_outbound": true,
+            "status": "QUEUED",
+            "error_code": null,
+            "error_message": null,
+            "message_handle": "073c1408-a6d9-48e2-ae8c-01f06443833",
+            "date_sent": "2021-05-19T23:07:23.371Z",
+            "date_updated": "2021-05-19T23:07:23.371Z",
+            "from_number": "+16468528190",
+            "number": ["+11112223333", "+13332221111"],
+            "to_number": ["+11112223333", "+13332221111"],
+            "was_downgraded": null,
+            "plan": "blue",
+            "media_url": "",
+            "message_type": "group",
+            "group_id": "66e3b90d-4447-43c6-9439-15a69408ac2"
+        }))
+        .unwrap();
+
+        let reply = response
+            .reply_builder()
+            .content("Reply".into())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            reply.group_id,
+            Some("66e3b90d-4447-43c6-9439-15a69408ac2".into())
+        );
+        assert!(reply.numbers.is_none());
+    }
+
+    #[test]
+    fn test_group_message_response_failed_recipients() {
+        let response: models::GroupMessageResponse = serde_json::from_value(json!({
+            "accountEmail": "YOUR EMAIL",
+            "content": "Hello world",
+            "is_outbound": true,
+            "status": "SENT",
+            "error_code": null,
+            "error_message": null,
+            "message_handle": "073c1408-a6d9-48e2-ae8c-01f06443833",
+            "date_sent": "2021-05-19T23:07:23.371Z",
+            "date_updated": "2021-05-19T23:07:23.371Z",
+            "from_number": "+16468528190",
+            "number": ["+14155552671", "+12125551234"],
+            "to_number": ["+14155552671", "+12125551234"],
+            "was_downgraded": null,
+            "plan": "blue",
+            "media_url": "",
+            "message_type": "group",
+            "group_id": "66e3b90d-4447-43c6-9439-15a69408ac2",
+            "recipients": [
+                { "number": "+14155552671", "status": "SENT" },
+                { "number": "+12125551234", "status": "FAILED", "error": "Number unreachable" }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(response.failed_recipients(), vec!["+12125551234"]);
+        assert_eq!(
+            response.recipients[1].error.as_deref(),
+            Some("Number unreachable")
+        );
+    }
+
+    #[test]
+    fn test_clone_for_builds_messages_from_template() {
+        let template = MessageBuilder::new("+14155552671")
+            .content("Your order has shipped".into())
+            .send_style(SendStyle::Celebration);
+
+        let first = template
+            .clone_for("+12125551234".parse().unwrap())
+            .build()
+            .unwrap();
+        let second = template
+            .clone_for("+12025550123".parse().unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(first.number, "+12125551234");
+        assert_eq!(second.number, "+12025550123");
+        assert_eq!(first.content, second.content);
+        assert_eq!(first.send_style, Some(SendStyle::Celebration));
+        assert_eq!(first.send_style, second.send_style);
+    }
+
+    #[test]
+    fn test_get_messages_params_query_string() {
+        let query = |params: &GetMessagesParams| {
+            reqwest::Client::new()
+                .get("https://api.sendblue.co/api/v2/messages")
+                .query(params)
+                .build()
+                .unwrap()
+                .url()
+                .query()
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        assert_eq!(query(&GetMessagesParams::default()), "");
+
+        let params = GetMessagesParams {
+            number: Some("+1 (415) 555-2671".into()),
+            limit: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(query(&params), "number=%2B14155552671&limit=10");
+    }
+
+    #[test]
+    fn test_message_to_and_group_message_empty() {
+        let mut message = models::Message::to("+14155552671".parse().unwrap());
+        message.content = Some("Hello, world!".into());
+        assert_eq!(
+            message,
+            MessageBuilder::new("+14155552671")
+                .content("Hello, world!".into())
+                .build()
+                .unwrap()
+        );
+
+        let mut group_message = GroupMessage::empty();
+        group_message.group_id = Some("66e3b90d-4447-43c6-9439-15a69408ac2".into());
+        group_message.content = Some("Hello, group!".into());
+        assert_eq!(
+            group_message,
+            MessageBuilder::new_group()
+                .group_id("66e3b90d-4447-43c6-9439-15a69408ac2".into())
+                .content("Hello, group!".into())
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_group_message_response_with_malformed_number() {
+        let response: models::GroupMessageResponse = serde_json::from_value(json!({
+            "accountEmail": "YOUR EMAIL",
+            "content": "Hello world",
+            "is_outbound": true,
+            "status": "QUEUED",
+            "error_code": null,
+            "error_message": null,
+            "message_handle": "073c1408-a6d9-48e2-ae8c-01f06443833",
+            "date_sent": "2021-05-19T23:07:23.371Z",
+            "date_updated": "2021-05-19T23:07:23.371Z",
+            "from_number": "+16468528190",
+            "number": ["+14155552671", "not-a-number"],
+            "to_number": ["+14155552671", "not-a-number"],
+            "was_downgraded": null,
+            "plan": "blue",
+            "media_url": "",
+            "message_type": "group",
+            "group_id": "66e3b90d-4447-43c6-9439-15a69408ac2",
+            "recipients": [
+                { "number": "+14155552671", "status": "SENT" },
+                { "number": "12-34", "status": "FAILED" }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(response.unparsed_numbers(), vec!["not-a-number", "12-34"]);
+        assert_eq!(response.failed_recipients(), vec!["12-34"]);
+    }
+
+    #[test]
+    fn test_send_style_all_round_trips() {
+        let styles = SendStyle::all();
+        assert_eq!(styles.len(), 13);
+        assert!(!styles.contains(&SendStyle::Default));
+        for style in styles {
+            assert_eq!(&SendStyle::from(style.as_str()), style);
+            assert!(!style.label().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_message_type_is_serialized_only_when_set() {
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .build()
+            .unwrap();
+        let body = serde_json::to_value(&message).unwrap();
+        assert!(body.get("message_type").is_none());
+
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .message_type(models::MessageType::Message)
+            .build()
+            .unwrap();
+        let body = serde_json::to_value(&message).unwrap();
+        assert_eq!(body["message_type"], "message");
+    }
+
+    #[test]
+    fn test_message_event_from_status_callback() {
+        let callback = |status: &str| -> models::MessageStatusCallback {
+            serde_json::from_value(json!({
+                "accountEmail": "support@sendblue.co",
+                "content": "Hello, world!",
+                "is_outbound": true,
+                "status": status,
+                "error_code": 4001,
+                "error_message": null,
+                "error_detail": "Too many requests",
+                "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+                "date_sent": "2020-04-17T22:49:34.166Z",
+                "date_updated": "2020-04-17T22:49:35.000Z",
+                "from_number": "+16468528190",
+                "number": "+14155552671",
+                "to_number": "+14155552671",
+                "opted_out": false
+            }))
+            .unwrap()
+        };
+
+        assert_eq!(
+            models::MessageEvent::from(callback("DELIVERED")),
+            models::MessageEvent::Delivered
+        );
+        assert_eq!(
+            models::MessageEvent::from(callback("ERROR")),
+            models::MessageEvent::Failed {
+                error_code: Some(models::ErrorCode::RateLimitExceeded),
+                error_message: Some("Too many requests".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_unmodeled_response_fields_are_kept() {
+        let body = json!({
+            "accountEmail": "YOUR EMAIL",
+            "content": "Hello, world!",
+            "is_outbound": true,
+            "status": "QUEUED",
+            "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+            "date_sent": "2020-04-17T22:49:34.166Z",
+            "date_updated": "2020-04-17T22:49:34.166Z",
+            "from_number": "+16468528190",
+            "number": "+14155552671",
+            "to_number": "+14155552671",
+            "opted_out": false,
+            "carrier": { "name": "Example Wireless" }
+        });
+        let response = serde_json::from_value::<MessageResponse>(body.clone());
+
+        if cfg!(feature = "strict") {
+            assert!(response.is_err());
+            return;
+        }
+        let response = response.unwrap();
+        assert_eq!(
+            response.extra.get("carrier"),
+            Some(&json!({ "name": "Example Wireless" }))
+        );
+        assert_eq!(response.error_code, None);
+
+        let serialized = serde_json::to_value(&response).unwrap();
+        assert_eq!(serialized["carrier"], body["carrier"]);
+        assert!(serialized.get("error_code").is_none());
+        assert_eq!(
+            serde_json::from_value::<MessageResponse>(serialized).unwrap(),
+            response
+        );
+    }
+
+    #[test]
+    fn test_send_at_in_the_past_is_rejected() {
+        let result = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .send_at(chrono::Utc::now() - chrono::Duration::minutes(5))
+            .build();
+        assert!(matches!(result, Err(SendblueError::ValidationError(_))));
+
+        let send_at = chrono::Utc::now() + chrono::Duration::hours(1);
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .send_at(send_at)
             .build()
             .unwrap();
+        let body = serde_json::to_value(&message).unwrap();
+        assert_eq!(body["send_at"], json!(send_at));
+    }
 
-        let result = client.send(&message).await;
-        if let Err(e) = &result {
-            error!("Error in test_send_message_success: {:?}", e);
+    #[test]
+    fn test_empty_content_reports_field_error() {
+        let result = MessageBuilder::new("+14155552671")
+            .content(String::new())
+            .build();
+
+        match result {
+            Err(SendblueError::Validation(errors)) => {
+                assert!(errors.field_errors().contains_key("content"))
+            }
+            other => panic!("expected a validation error, got {:?}", other),
         }
-        assert!(result.is_ok());
-        let response = result.unwrap();
-        assert_eq!(response.status, Status::Queued);
+    }
+
+    #[test]
+    fn test_whitespace_only_content_fails_to_build() {
+        let message = MessageBuilder::new("+14155552671")
+            .content("\n\t ".into())
+            .build();
+        assert!(matches!(
+            message,
+            Err(SendblueError::Validation(errors)) if errors.field_errors().contains_key("content")
+        ));
+
+        let group_message = MessageBuilder::new_group()
+            .group_id("66e3b90d-4447-43c6-9439-15a69408ac2".into())
+            .content("\n\t ".into())
+            .build();
+        assert!(matches!(group_message, Err(SendblueError::Validation(_))));
+    }
+
+    #[test]
+    fn test_invalid_number_fails_to_build() {
+        let result = MessageBuilder::new("+10722971673")
+            .content("Hello, world!".into())
+            .build();
+
+        match result {
+            Err(SendblueError::ValidationError(message)) => {
+                assert!(message.contains("+10722971673"))
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unparseable_number_is_reported_by_build() {
+        let result = MessageBuilder::new("not a number")
+            .content("Hello, world!".into())
+            .build();
+        assert!(matches!(result, Err(SendblueError::ValidationError(_))));
+
+        let result = MessageBuilder::<GroupMessage>::new_group()
+            .numbers(["+14155552671", "not a number"])
+            .content("Hello, group!".into())
+            .build();
+        assert!(matches!(result, Err(SendblueError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_group_message_too_many_recipients() {
+        let numbers = (0..=models::message::MAX_GROUP_PARTICIPANTS)
+            .map(|i| format!("+1555000{:04}", i))
+            .collect::<Vec<_>>();
+        let result = MessageBuilder::<GroupMessage>::new_group()
+            .numbers(numbers)
+            .content("Hello, group!".into())
+            .build();
+
+        match result {
+            Err(SendblueError::ValidationError(message)) => assert!(message.contains("33")),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_group_message_dedups_recipients() {
+        let group_message = MessageBuilder::<GroupMessage>::new_group()
+            .numbers(["+14155552671", "+14155552672", "+14155552671"])
+            .content("Hello, group!".into())
+            .build()
+            .unwrap();
+
         assert_eq!(
-            response.message_handle,
-            "dfd747ba-5600-4a8a-804a-a614a0fbc1c5"
+            group_message.numbers.unwrap(),
+            ["+14155552671", "+14155552672"]
         );
-        mock.assert_hits(1);
     }
 
-    #[ignore]
-    #[tokio::test]
-    async fn test_get_messages_success() {
-        let mock_server = MockServer::start();
-        let mock = mock_server.mock(|when, then| {
-            when.method("GET")
-                .path("/accounts/messages")
-                .header("sb-api-key-id", "test_key")
-                .header("sb-api-secret-key", "test_secret");
-            then.status(200)
-                .header("content-type", "application/json")
-                .json_body(json!({
-                    "messages": [
-                        {
-                            "error_message": null,
-                            "date": "2023-09-21T20:22:05.066Z",
-                            "to_number": "+10722971673",
-                            "date_sent": {
-                                "_seconds": 1695327725,
-                                "_nanoseconds": 66000000
-                            },
-                            "date_updated": {
-                                "_seconds": 1695327725,
-                                "_nanoseconds": 456000000
-                            },
-                            "error_detail": null,
-                            "phoneID": "worker_5s_spacegray_1",
-                            "message_type": "message",
-                            "uuid": "595578e5-6701-4b89-ac9b-28cbfe99cd",
-                            "media_url": "",
-                            "content": "testn - Sent using sendblue.co",
-                            "send_style": "",
-                            "callback_url": "",
-                            "is_outbound": true,
-                            "allow_sms": false,
-                            "accountEmail": "youremail@gmail.com",
-                            "was_downgraded": null,
-                            "group_id": "",
-                            "from_number": "+88888888888",
-                            "error_code": 22,
-                            "row_id": "4444",
-                            "status": "ERROR"
-                        }
-                    ]
-                }));
-        });
+    #[test]
+    fn test_allow_sms_is_only_sent_when_set() {
+        let builder = || MessageBuilder::new("+14155552671").content("Hello, world!".into());
 
-        let client = create_client_with_mock_url(&mock_server.base_url());
-        let params = GetMessagesParamsBuilder::new().build();
+        let body = serde_json::to_value(builder().build().unwrap()).unwrap();
+        assert!(body.get("allow_sms").is_none());
 
-        let result = client.get_messages(params).await;
-        if let Err(e) = &result {
-            error!("Error in test_get_messages_success: {:?}", e);
+        let body = serde_json::to_value(builder().allow_sms(true).build().unwrap()).unwrap();
+        assert_eq!(body["allow_sms"], true);
+    }
+
+    #[test]
+    fn test_send_style_raw_is_sent_verbatim() {
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .send_style_raw("spin")
+            .build()
+            .unwrap();
+
+        let body = serde_json::to_value(&message).unwrap();
+        assert_eq!(body["send_style"], "spin");
+    }
+
+    #[test]
+    fn test_send_style_display_matches_serde() {
+        for style in [
+            SendStyle::Celebration,
+            SendStyle::ShootingStar,
+            SendStyle::Fireworks,
+            SendStyle::Lasers,
+            SendStyle::Love,
+            SendStyle::Confetti,
+            SendStyle::Balloons,
+            SendStyle::Spotlight,
+            SendStyle::Echo,
+            SendStyle::Invisible,
+            SendStyle::Gentle,
+            SendStyle::Loud,
+            SendStyle::Slam,
+            SendStyle::Default,
+            SendStyle::Unknown("spin".into()),
+        ] {
+            assert_eq!(serde_json::to_value(&style).unwrap(), style.to_string());
         }
-        assert!(result.is_ok());
-        let response = result.unwrap();
-        assert_eq!(response.messages.len(), 2);
-        mock.assert_hits(1);
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_send_style_schema_includes_default_variant() {
+        let schema = serde_json::to_value(schemars::schema_for!(models::SendStyle)).unwrap();
+        let values = schema["enum"].as_array().unwrap();
+
+        assert_eq!(schema["type"], json!("string"));
+        assert!(values.contains(&json!("celebration")));
+        assert!(values.contains(&json!("")));
     }
 
     #[tokio::test]
-    async fn test_send_group_message_success() {
+    async fn test_send_forwards_custom_headers() {
         let mock_server = MockServer::start();
         let mock = mock_server.mock(|when, then| {
             when.method("POST")
-                .path("/send-group-message")
+                .path("/send-message")
                 .header("sb-api-key-id", "test_key")
-                .header("sb-api-secret-key", "test_secret");
-            then.status(200)
-                .header("content-type", "application/json")
-                .json_body(json!({
-                  "accountEmail": "YOUR EMAIL",
-                  "content": "Hello world",
-                  "is_outbound": true,
-                  "status": "QUEUED",
-                  "error_code": null,
-                  "error_message": null,
-                  "message_handle": "073c1408-a6d9-48e2-ae8c-01f06443833",
-                  "date_sent": "2021-05-19T23:07:23.371Z",
-                  "date_updated": "2021-05-19T23:07:23.371Z",
-                  "from_number": "+19998887777",
-                  "number": ["+11112223333", "+13332221111"],
-                  "to_number": ["+11112223333", "+13332221111"],
-                  "was_downgraded": null,
-                  "plan": "blue",
-                  "media_url": "https://picsum.photos/200/300.jpg",
-                  "message_type": "group",
-                  "group_id": "66e3b90d-4447-43c6-9439-15a69408ac2"
-                }));
+                .header("sb-api-secret-key", "test_secret")
+                .header("x-tenant", "acme")
+                .header("x-request-id", "1234abcd");
+            then.status(400);
         });
 
-        let client = create_client_with_mock_url(&mock_server.base_url());
-        let phone_number1 = parse(None, "+10722971673").unwrap();
-        let phone_number2 = parse(None, "+1234567891").unwrap();
-        let group_message = MessageBuilder::<GroupMessage>::new_group()
-            .numbers(vec![phone_number1, phone_number2])
-            .content("Test group message".into())
+        let client = create_client_with_mock_url(&mock_server.base_url())
+            .with_default_header(
+                HeaderName::from_static("x-tenant"),
+                HeaderValue::from_static("acme"),
+            )
+            .with_default_header(
+                HeaderName::from_static("sb-api-key-id"),
+                HeaderValue::from_static("spoofed"),
+            );
+        let message = MessageBuilder::new("+14155552671")
+            .content("Test message".into())
             .build()
             .unwrap();
 
-        let result = client.send(&group_message).await;
-        if let Err(e) = &result {
-            error!("Error in test_send_group_message_success: {:?}", e);
-        }
-        assert!(result.is_ok());
-        let response = result.unwrap();
-        assert_eq!(response.status, Status::Queued);
-        assert_eq!(
-            response.message_handle,
-            "073c1408-a6d9-48e2-ae8c-01f06443833"
-        );
+        let mut headers = HeaderMap::new();
+        headers.insert("x-request-id", HeaderValue::from_static("1234abcd"));
+        headers.insert("sb-api-secret-key", HeaderValue::from_static("spoofed"));
+
+        let result = client.send_with_headers(&message, &headers).await;
+        assert!(matches!(result, Err(SendblueError::BadRequest { .. })));
         mock.assert_hits(1);
     }
 
     #[tokio::test]
-    async fn test_evaluate_service_success() {
+    async fn test_evaluate_service_unexpected_content_type() {
         let mock_server = MockServer::start();
         let mock = mock_server.mock(|when, then| {
-            when.method("GET")
-                .path("/evaluate-service")
-                .header("sb-api-key-id", "test_key")
-                .header("sb-api-secret-key", "test_secret");
+            when.method("GET").path("/evaluate-service");
             then.status(200)
-                .header("content-type", "application/json")
-                .json_body(json!({
-                    "number": "+10722971673",
-                    "service": "iMessage"
-                }));
+                .header("content-type", "text/html; charset=utf-8")
+                .body("<html><body>502 Bad Gateway</body></html>");
         });
 
         let client = create_client_with_mock_url(&mock_server.base_url());
-        let phone_number = parse(None, "+10722971673").unwrap();
+        let phone_number: models::PhoneNumber = "+14155552671".parse().unwrap();
         let evaluate_service = EvaluateServiceBuilder::new().number(phone_number).build();
 
         let result = client.evaluate_service(&evaluate_service).await;
-        if let Err(e) = &result {
-            error!("Error in test_evaluate_service_success: {:?}", e);
+        match result {
+            Err(SendblueError::UnexpectedContentType {
+                content_type,
+                body_snippet,
+            }) => {
+                assert_eq!(content_type.as_deref(), Some("text/html; charset=utf-8"));
+                assert!(body_snippet.contains("502 Bad Gateway"));
+            }
+            other => panic!("unexpected result: {:?}", other),
         }
-        assert!(result.is_ok());
-        let response = result.unwrap();
-        assert_eq!(response.number, "+10722971673");
-        assert_eq!(response.service, "iMessage");
         mock.assert_hits(1);
     }
 
-    #[tokio::test]
-    async fn test_send_typing_indicator_success() {
-        let mock_server = MockServer::start();
-        let mock = mock_server.mock(|when, then| {
-            when.method("POST")
-                .path("/send-typing-indicator")
-                .header("sb-api-key-id", "test_key")
-                .header("sb-api-secret-key", "test_secret");
-            then.status(200)
-                .header("content-type", "application/json")
-                .json_body(json!({
-                    "number": "+10722971673",
-                    "status": "SENT"
-                }));
+    #[test]
+    fn test_error_code_numeric_and_string() {
+        let mut body = json!({
+            "accountEmail": "YOUR EMAIL",
+            "content": "Hello world!",
+            "is_outbound": true,
+            "status": "FAILED",
+            "error_code": 10001,
+            "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+            "date_sent": "2023-09-27T16:35:32.287Z",
+            "date_updated": "2023-09-27T16:35:32.703Z",
+            "from_number": "+16468528190",
+            "number": "+16468528190",
+            "to_number": "+16468528190",
+            "media_url": "",
+            "opted_out": false
         });
 
-        let client = create_client_with_mock_url(&mock_server.base_url());
-        let phone_number = parse(None, "+10722971673").unwrap();
+        let response: models::MessageResponse = serde_json::from_value(body.clone()).unwrap();
+        assert_eq!(
+            response.error_code,
+            Some(models::ErrorCode::MessageFailedToSend)
+        );
 
-        let result = client.send_typing_indicator(&phone_number).await;
-        if let Err(e) = &result {
-            error!("Error in test_send_typing_indicator_success: {:?}", e);
-        }
-        assert!(result.is_ok());
-        let response = result.unwrap();
-        assert_eq!(response.status, TypingIndicatorStatus::Sent);
-        mock.assert_hits(1);
+        body["error_code"] = json!("10001");
+        let response: models::MessageResponse = serde_json::from_value(body.clone()).unwrap();
+        assert_eq!(
+            response.error_code,
+            Some(models::ErrorCode::MessageFailedToSend)
+        );
+        assert_eq!(
+            serde_json::to_value(&response).unwrap()["error_code"],
+            10001
+        );
+
+        body["error_code"] = json!(22);
+        let response: models::MessageResponse = serde_json::from_value(body.clone()).unwrap();
+        assert_eq!(response.error_code, Some(models::ErrorCode::Other(22)));
+        assert_eq!(serde_json::to_value(&response).unwrap()["error_code"], 22);
+
+        body["error_code"] = json!(-1);
+        let response: models::MessageResponse = serde_json::from_value(body).unwrap();
+        assert_eq!(response.error_code, Some(models::ErrorCode::Unknown));
+        assert!(serde_json::to_value(&response).unwrap()["error_code"].is_null());
     }
 
-    #[tokio::test]
-    async fn test_send_typing_indicator_failure() {
-        let mock_server = MockServer::start();
-        let mock = mock_server.mock(|when, then| {
-            when.method("POST")
-                .path("/send-typing-indicator")
-                .header("sb-api-key-id", "test_key")
-                .header("sb-api-secret-key", "test_secret");
-            then.status(400)
-                .header("content-type", "application/json")
-                .json_body(json!({
-                    "status": "ERROR",
-                    "error_message": "Failed to send typing indicator"
-                }));
+    #[test]
+    fn test_message_response_unknown_send_style() {
+        let mut body = json!({
+            "accountEmail": "YOUR EMAIL",
+            "content": "Hello world!",
+            "is_outbound": true,
+            "status": "QUEUED",
+            "error_code": null,
+            "error_message": null,
+            "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+            "date_sent": "2023-09-27T16:35:32.287Z",
+            "date_updated": "2023-09-27T16:35:32.703Z",
+            "from_number": "+16468528190",
+            "number": "+16468528190",
+            "to_number": "+16468528190",
+            "was_downgraded": null,
+            "plan": "dedicated",
+            "media_url": "",
+            "message_type": "message",
+            "group_id": "",
+            "participants": [],
+            "send_style": "new_fancy_effect",
+            "opted_out": false,
+            "error_detail": null
         });
 
-        let client = create_client_with_mock_url(&mock_server.base_url());
-        let phone_number = parse(None, "+10722971673").unwrap();
+        let response: models::MessageResponse = serde_json::from_value(body.clone()).unwrap();
+        assert_eq!(
+            response.send_style,
+            Some(models::SendStyle::Unknown("new_fancy_effect".into()))
+        );
 
-        let result = client.send_typing_indicator(&phone_number).await;
-        if let Err(e) = &result {
-            error!("Error in test_send_typing_indicator_failure: {:?}", e);
-        }
-        assert!(result.is_err());
-        let response = result.unwrap_err();
-        if let SendblueError::BadRequest(body) = response {
-            let expected_error = json!({
-                "status": "ERROR",
-                "error_message": "Failed to send typing indicator"
-            });
-            let actual_error: serde_json::Value = serde_json::from_str(&body).unwrap();
-            assert_eq!(actual_error, expected_error);
+        body.as_object_mut().unwrap().remove("send_style");
+        let response: models::MessageResponse = serde_json::from_value(body).unwrap();
+        assert_eq!(response.send_style, None);
+    }
+
+    #[test]
+    fn test_message_response_media_url_empty_or_null() {
+        let mut body = json!({
+            "accountEmail": "YOUR EMAIL",
+            "content": "Hello world!",
+            "is_outbound": true,
+            "status": "QUEUED",
+            "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+            "date_sent": "2023-09-27T16:35:32.287Z",
+            "date_updated": "2023-09-27T16:35:32.703Z",
+            "from_number": "+16468528190",
+            "number": "+16468528190",
+            "to_number": "+16468528190",
+            "media_url": "",
+            "opted_out": false
+        });
+
+        for media_url in [json!(""), json!(null)] {
+            body["media_url"] = media_url;
+            let response: models::MessageResponse = serde_json::from_value(body.clone()).unwrap();
+            assert_eq!(response.media_url, None);
         }
-        mock.assert_hits(1);
+
+        body["media_url"] = json!("https://example.com/image.png");
+        let response: models::MessageResponse = serde_json::from_value(body).unwrap();
+        assert_eq!(
+            response.media_url.as_deref(),
+            Some("https://example.com/image.png")
+        );
+    }
+
+    #[test]
+    fn test_message_response_media() {
+        let mut response: models::MessageResponse = serde_json::from_value(json!({
+            "accountEmail": "YOUR EMAIL",
+            "content": "",
+            "is_outbound": true,
+            "status": "QUEUED",
+            "message_handle": "dfd747ba-5600-4a8a-804a-a614a0fbc1c5",
+            "date_sent": "2023-09-27T16:35:32.287Z",
+            "date_updated": "2023-09-27T16:35:32.703Z",
+            "from_number": "+16468528190",
+            "number": "+16468528190",
+            "to_number": "+16468528190",
+            "media_url": "https://storage.sendblue.co/images/cat.jpg",
+            "opted_out": false
+        }))
+        .unwrap();
+
+        assert_eq!(
+            response.media(),
+            Some(MediaUrl::new("https://storage.sendblue.co/images/cat.jpg").unwrap())
+        );
+
+        response.media_url = None;
+        assert_eq!(response.media(), None);
+    }
+
+    #[test]
+    fn test_retrieved_message_date_formats() {
+        let response: GetMessagesResponse = serde_json::from_value(json!({
+            "messages": [
+                {
+                    "date": "2023-09-21T20:22:05.066Z",
+                    "type": "message",
+                    "uuid": "595578e5-6701-4b89-ac9b-28cbfe99cd",
+                    "is_outbound": true,
+                    "accountEmail": "youremail@gmail.com",
+                    "status": "SENT",
+                    "date_sent": {
+                        "_seconds": 1695327725,
+                        "_nanoseconds": 66000000
+                    },
+                    "date_updated": "2023-09-21T20:22:05.456Z"
+                }
+            ]
+        }))
+        .unwrap();
+
+        let message = &response[0];
+        assert_eq!(
+            message.date_sent.unwrap().to_rfc3339(),
+            "2023-09-21T20:22:05.066+00:00"
+        );
+        assert_eq!(
+            message.date_updated.unwrap().to_rfc3339(),
+            "2023-09-21T20:22:05.456+00:00"
+        );
+    }
+
+    #[test]
+    fn test_firestore_timestamp_round_trip() {
+        let timestamp: models::FirestoreTimestamp =
+            serde_json::from_value(json!("2023-09-21T20:22:05.066Z")).unwrap();
+
+        let value = serde_json::to_value(timestamp).unwrap();
+        assert_eq!(
+            value,
+            json!({ "_seconds": 1695327725, "_nanoseconds": 66000000 })
+        );
+        assert_eq!(
+            serde_json::from_value::<models::FirestoreTimestamp>(value).unwrap(),
+            timestamp
+        );
     }
 
     #[tokio::test]
-    async fn test_send_message_failure() {
+    async fn test_attach_request_body() {
         let mock_server = MockServer::start();
         let mock = mock_server.mock(|when, then| {
-            when.method("POST")
-                .path("/send-message")
-                .header("sb-api-key-id", "test_key")
-                .header("sb-api-secret-key", "test_secret");
+            when.method("POST").path("/send-message");
             then.status(400)
                 .header("content-type", "application/json")
-                .json_body(json!({
-                    "status": "ERROR",
-                    "message": "Bad request"
-                }));
+                .body(r#"{"status":"ERROR","error_message":"Invalid number"}"#);
         });
+        let message = MessageBuilder::new("+14155552671")
+            .content("Hello, world!".into())
+            .build()
+            .unwrap();
 
         let client = create_client_with_mock_url(&mock_server.base_url());
-        let phone_number = parse(None, "+10722971673").unwrap();
-        let message = MessageBuilder::new(phone_number)
-            .content("Test message".into())
+        let error = client.send(&message).await.unwrap_err();
+        assert!(error.request_body().is_none());
+
+        let client = SendblueClient::builder("test_key".into(), "test_secret".into())
+            .base_url(mock_server.base_url())
+            .allow_http(true)
+            .attach_request_body(true)
             .build()
             .unwrap();
+        let error = client.send(&message).await.unwrap_err();
+        assert!(matches!(error, SendblueError::BadRequest { .. }));
+        let request_body: serde_json::Value =
+            serde_json::from_str(error.request_body().unwrap()).unwrap();
+        assert_eq!(request_body["number"], "+14155552671");
+        assert_eq!(request_body["content"], "Hello, world!");
 
-        let result = client.send(&message).await;
-        if let Err(e) = &result {
-            error!("Error in test_send_message_failure: {:?}", e);
-        }
-        assert!(result.is_err());
-        mock.assert_hits(1);
+        let error = client.send_with_meta(&message).await.unwrap_err();
+        assert!(error.request_body().is_some());
+        mock.assert_hits(3);
     }
 }
- */
@@ -0,0 +1,65 @@
+//! Account Model
+//!
+//! This module provides the data models for the account information returned by the Sendblue API.
+
+use crate::models::PhoneNumber;
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Account information from the Sendblue API
+///
+/// The endpoint behind this isn't publicly documented, so the fields below are unverified.
+///
+/// The response comes back as JSON with the following fields:
+/// - `email`: The email of the account
+/// - `plan`: The plan the account is on
+/// - `numbers`: The phone numbers available to send from, in E.164 format
+/// - `usage`: The message usage of the current billing period
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct AccountInfo {
+    /// The email of the account (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub email: Option<String>,
+    /// The plan the account is on (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub plan: Option<String>,
+    /// The phone numbers available to send from
+    #[serde(default)]
+    pub numbers: Vec<PhoneNumber>,
+    /// The message usage of the current billing period (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub usage: Option<AccountUsage>,
+}
+
+/// Message usage of a Sendblue account
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct AccountUsage {
+    /// The number of messages sent in the current billing period
+    #[serde(default)]
+    pub messages_sent: u64,
+    /// The number of messages included in the plan, if limited (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub messages_limit: Option<u64>,
+}
+
+impl AccountUsage {
+    /// Returns how many messages are left in the current billing period, if the plan is limited
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::AccountUsage;
+    ///
+    /// let usage = AccountUsage { messages_sent: 40, messages_limit: Some(100) };
+    /// assert_eq!(usage.messages_remaining(), Some(60));
+    /// ```
+    pub fn messages_remaining(&self) -> Option<u64> {
+        self.messages_limit
+            .map(|limit| limit.saturating_sub(self.messages_sent))
+    }
+}
@@ -3,6 +3,11 @@
 //! This module provides the data model for callback URLs used in the Sendblue API.
 
 use crate::traits::Url;
+#[cfg(feature = "schemars")]
+use schemars::{
+    schema::{InstanceType, Schema, SchemaObject},
+    JsonSchema,
+};
 use serde::{Deserialize, Serialize};
 use url::Url as RawUrl;
 use validator::ValidationError;
@@ -49,7 +54,23 @@ impl<'de> Deserialize<'de> for CallbackUrl {
     where
         D: serde::Deserializer<'de>,
     {
-        let s: &str = Deserialize::deserialize(deserializer)?;
-        CallbackUrl::new(s).map_err(serde::de::Error::custom)
+        let s = String::deserialize(deserializer)?;
+        CallbackUrl::new(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for CallbackUrl {
+    fn schema_name() -> String {
+        "CallbackUrl".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("uri".to_string()),
+            ..Default::default()
+        }
+        .into()
     }
 }
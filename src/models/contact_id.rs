@@ -0,0 +1,56 @@
+//! Contact ID Model
+//!
+//! This module provides the data model for contact IDs used in the Sendblue API.
+
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, fmt, str::FromStr};
+
+/// Identifier of a contact, used to filter messages with `cid`
+///
+/// # Examples
+///
+/// ```
+/// use sendblue::models::ContactId;
+///
+/// let contact_id = ContactId::from("contact_1234");
+/// assert_eq!(contact_id.as_str(), "contact_1234");
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(transparent)]
+pub struct ContactId(String);
+
+impl ContactId {
+    /// Returns the contact ID as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ContactId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for ContactId {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl From<String> for ContactId {
+    fn from(contact_id: String) -> Self {
+        Self(contact_id)
+    }
+}
+
+impl From<&str> for ContactId {
+    fn from(contact_id: &str) -> Self {
+        Self(contact_id.to_string())
+    }
+}
@@ -0,0 +1,76 @@
+//! Dynamic Message Model
+//!
+//! This module provides wrappers for individual and group messages and their responses, so
+//! messages of mixed kinds can be stored together, e.g. in a queue of pending sends.
+
+use crate::{
+    models::{GroupMessage, GroupMessageResponse, Message, MessageResponse},
+    traits::SendableMessage,
+};
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Either an individual or a group message
+///
+/// Serializes exactly like the wrapped message. Send it with `SendblueClient::send_dyn`.
+///
+/// # Examples
+///
+/// ```
+/// use sendblue::models::{DynMessage, MessageBuilder};
+///
+/// let queue: Vec<DynMessage> = vec![
+///     MessageBuilder::new("+14155552671")
+///         .content("Hello, world!".into())
+///         .build()
+///         .unwrap()
+///         .into(),
+///     MessageBuilder::new_group()
+///         .numbers(["+14155552671", "+12125551234"])
+///         .content("Hello, group!".into())
+///         .build()
+///         .unwrap()
+///         .into(),
+/// ];
+///
+/// assert_eq!(queue[1].endpoint(), "/send-group-message");
+/// ```
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(untagged)]
+pub enum DynMessage {
+    Message(Message),
+    Group(GroupMessage),
+}
+
+impl DynMessage {
+    /// Returns the endpoint the wrapped message is sent to
+    pub fn endpoint(&self) -> &'static str {
+        match self {
+            DynMessage::Message(_) => Message::endpoint(),
+            DynMessage::Group(_) => GroupMessage::endpoint(),
+        }
+    }
+}
+
+impl From<Message> for DynMessage {
+    fn from(message: Message) -> Self {
+        DynMessage::Message(message)
+    }
+}
+
+impl From<GroupMessage> for DynMessage {
+    fn from(message: GroupMessage) -> Self {
+        DynMessage::Group(message)
+    }
+}
+
+/// Response to a `DynMessage`, matching the kind of message that was sent
+#[derive(Serialize, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(untagged)]
+pub enum DynMessageResponse {
+    Message(MessageResponse),
+    Group(GroupMessageResponse),
+}
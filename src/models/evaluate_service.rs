@@ -3,7 +3,9 @@
 //! This module provides the data models for evaluating if a number can send/receive iMessages,
 //! including the request and response structures.
 
-use phonenumber::PhoneNumber;
+use crate::{models::PhoneNumber, SendblueError};
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Request parameters for evaluating if a number can send/receive iMessages
@@ -14,10 +16,11 @@ use serde::{Deserialize, Serialize};
 /// use sendblue::models::EvaluateServiceBuilder;
 ///
 /// let request = EvaluateServiceBuilder::new()
-///     .number(phonenumber::parse(None, "+19999999999").unwrap())
+///     .number("+19999999999".parse().unwrap())
 ///     .build();
 /// ```
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct EvaluateService {
     pub number: PhoneNumber,
 }
@@ -30,7 +33,7 @@ pub struct EvaluateService {
 /// use sendblue::models::EvaluateServiceBuilder;
 ///
 /// let request = EvaluateServiceBuilder::new()
-///     .number(phonenumber::parse(None, "+19999999999").unwrap())
+///     .number("+19999999999".parse().unwrap())
 ///     .build();
 /// ```
 pub struct EvaluateServiceBuilder {
@@ -73,7 +76,57 @@ impl Default for EvaluateServiceBuilder {
 /// };
 /// ```
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct EvaluateServiceResponse {
     pub number: String,
-    pub service: String,
+    pub service: EvaluateServiceType,
+}
+
+/// Service a number can be reached with
+///
+/// Services this crate doesn't know about yet are kept as `Unknown` instead of failing to
+/// deserialize.
+///
+/// # Examples
+///
+/// ```
+/// use sendblue::models::EvaluateServiceType;
+///
+/// assert_eq!(EvaluateServiceType::from("iMessage"), EvaluateServiceType::IMessage);
+/// assert_eq!(EvaluateServiceType::from("SMS"), EvaluateServiceType::Sms);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum EvaluateServiceType {
+    #[serde(rename = "iMessage")]
+    IMessage,
+    #[serde(rename = "SMS")]
+    Sms,
+    #[serde(untagged)]
+    #[cfg_attr(feature = "schemars", schemars(skip))]
+    Unknown(String),
+}
+
+impl From<&str> for EvaluateServiceType {
+    /// Converts a raw service name into an `EvaluateServiceType`
+    ///
+    /// Known names map to their typed variant, anything else becomes `Unknown`.
+    fn from(service: &str) -> Self {
+        serde_json::from_value(serde_json::Value::String(service.to_owned()))
+            .unwrap_or_else(|_| EvaluateServiceType::Unknown(service.to_owned()))
+    }
+}
+
+/// Numbers split by the service they can be reached with
+///
+/// Returned by `SendblueClient::segment_contacts`.
+#[derive(Debug, Default)]
+pub struct ContactSegments {
+    /// Numbers that can receive iMessages
+    pub imessage: Vec<PhoneNumber>,
+    /// Numbers that can only receive SMS, or whose service isn't known to this crate
+    pub sms: Vec<PhoneNumber>,
+    /// Numbers that couldn't be evaluated, with the error that occurred
+    pub errored: Vec<(PhoneNumber, SendblueError)>,
 }
@@ -0,0 +1,56 @@
+//! Group ID Model
+//!
+//! This module provides the data model for group IDs used in the Sendblue API.
+
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, fmt, str::FromStr};
+
+/// Identifier of an existing group chat
+///
+/// # Examples
+///
+/// ```
+/// use sendblue::models::GroupId;
+///
+/// let group_id = GroupId::from("66e3b90d-4447-43c6-9439-15a69408ac2");
+/// assert_eq!(group_id.as_str(), "66e3b90d-4447-43c6-9439-15a69408ac2");
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(transparent)]
+pub struct GroupId(String);
+
+impl GroupId {
+    /// Returns the group ID as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for GroupId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for GroupId {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl From<String> for GroupId {
+    fn from(group_id: String) -> Self {
+        Self(group_id)
+    }
+}
+
+impl From<&str> for GroupId {
+    fn from(group_id: &str) -> Self {
+        Self(group_id.to_string())
+    }
+}
@@ -3,15 +3,35 @@
 //! This module provides the data model for media URLs used in the Sendblue API.
 
 use crate::traits::Url;
+#[cfg(feature = "schemars")]
 use schemars::{
     schema::{InstanceType, Schema, SchemaObject},
     JsonSchema,
 };
 use serde::{Deserialize, Serialize};
-use std::{fmt, ops::Deref, str::FromStr};
+use std::{fmt, ops::Deref, path::Path, str::FromStr};
 use url::Url as RawUrl;
 use validator::ValidationError;
 
+/// Largest media file in bytes that can be sent, the iMessage attachment limit of 100 MB
+pub const MAX_MEDIA_SIZE: u64 = 100 * 1000 * 1000;
+
+/// File extensions of media types Sendblue can deliver, with their MIME types
+const SUPPORTED_MEDIA_TYPES: &[(&str, &str)] = &[
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("png", "image/png"),
+    ("gif", "image/gif"),
+    ("mp4", "video/mp4"),
+    ("mov", "video/quicktime"),
+    ("caf", "audio/x-caf"),
+    ("mp3", "audio/mpeg"),
+    ("m4a", "audio/mp4"),
+    ("wav", "audio/wav"),
+    ("pdf", "application/pdf"),
+    ("vcf", "text/vcard"),
+];
+
 /// A URL for general media, can be any valid URL
 ///
 /// # Examples
@@ -25,6 +45,74 @@ use validator::ValidationError;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MediaUrl(RawUrl);
 
+impl MediaUrl {
+    /// Guesses the MIME type of the media from the file extension of the URL path
+    ///
+    /// Returns `None` if the URL has no extension or the extension is not a media type
+    /// Sendblue is known to support, such as `.svg` or `.heic`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::MediaUrl;
+    /// use sendblue::traits::Url;
+    ///
+    /// let media_url = MediaUrl::new("https://example.com/media.JPG").unwrap();
+    /// assert_eq!(media_url.guess_mime(), Some("image/jpeg"));
+    ///
+    /// let media_url = MediaUrl::new("https://example.com/image.svg").unwrap();
+    /// assert_eq!(media_url.guess_mime(), None);
+    /// ```
+    pub fn guess_mime(&self) -> Option<&'static str> {
+        let extension = Path::new(self.0.path()).extension()?.to_str()?;
+        SUPPORTED_MEDIA_TYPES
+            .iter()
+            .find(|(ext, _)| ext.eq_ignore_ascii_case(extension))
+            .map(|(_, mime)| *mime)
+    }
+
+    /// Returns the file extension used for a supported MIME type, e.g. `"png"` for `image/png`
+    #[cfg(feature = "upload")]
+    pub(crate) fn extension_for_mime(mime: &str) -> Option<&'static str> {
+        SUPPORTED_MEDIA_TYPES
+            .iter()
+            .find(|(_, supported)| supported.eq_ignore_ascii_case(mime))
+            .map(|(ext, _)| *ext)
+    }
+
+    /// Checks that the URL doesn't have a file extension Sendblue is not known to support
+    ///
+    /// URLs without an extension pass, since their type is only known once fetched. The error
+    /// names the extension but not the URL, which may carry signed query parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::MediaUrl;
+    /// use sendblue::traits::Url;
+    ///
+    /// assert!(MediaUrl::new("https://example.com/media.png").unwrap().check_supported().is_ok());
+    /// assert!(MediaUrl::new("https://example.com/image.heic").unwrap().check_supported().is_err());
+    /// ```
+    pub fn check_supported(&self) -> Result<(), ValidationError> {
+        let Some(extension) = Path::new(self.0.path()).extension() else {
+            return Ok(());
+        };
+        if self.guess_mime().is_some() {
+            return Ok(());
+        }
+        let mut error = ValidationError::new("unsupported_media_type");
+        error.message = Some(
+            format!(
+                "unsupported media file extension .{}",
+                extension.to_string_lossy()
+            )
+            .into(),
+        );
+        Err(error)
+    }
+}
+
 impl Url for MediaUrl {
     fn new(url: &str) -> Result<Self, ValidationError> {
         let raw_url = RawUrl::parse(url).map_err(|_| ValidationError::new("invalid url format"))?;
@@ -50,8 +138,7 @@ impl FromStr for MediaUrl {
     type Err = ValidationError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let raw_url = RawUrl::parse(s).map_err(|_| ValidationError::new("invalid url format"))?;
-        Ok(Self(raw_url))
+        Self::new(s)
     }
 }
 
@@ -82,6 +169,7 @@ impl Deref for MediaUrl {
     }
 }
 
+#[cfg(feature = "schemars")]
 impl JsonSchema for MediaUrl {
     fn schema_name() -> String {
         "MediaUrl".to_string()
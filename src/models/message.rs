@@ -9,18 +9,28 @@ use crate::{
         /* phonenumber::deserialize_phone_number, */ /* phonenumber::{deserialize_option_phone_number,
         deserialize_option_vec_phone_number, deserialize_phone_number,
         deserialize_vec_phone_number, serialize_phone_number}, */
-        CallbackUrl, MediaUrl, SendStyle,
+        phonenumber::serialize_option_e164, CallbackUrl, ContactId, FirestoreTimestamp, GroupId,
+        MediaUrl, MessageHandle, MessageType, PhoneNumber, SendStyle,
     },
-    traits::SendableMessage,
+    traits::{SendableMessage, Url},
     SendblueError,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
 #[cfg(feature = "schemars")]
-use schemars::{schema::Schema, schema_for, JsonSchema};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use serde_with::{serde_as, skip_serializing_none};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    ops::Deref,
+};
 use validator::Validate;
 
+/// Maximum number of recipients in a group message
+pub const MAX_GROUP_PARTICIPANTS: usize = 32;
+
 /// Message to be sent using the Sendblue API
 ///
 /// # Examples
@@ -28,18 +38,19 @@ use validator::Validate;
 /// ```
 /// use sendblue::models::{Message, MessageBuilder};
 ///
-/// let message = MessageBuilder::new(phonenumber::parse(None, "+1234567890").unwrap())
+/// let message = MessageBuilder::new("+14155552671")
 ///     .content("Hello, world!".into())
 ///     .build()
 ///     .unwrap();
 /// ```
-#[derive(Serialize, Deserialize, Validate, Debug)]
+#[derive(Serialize, Deserialize, Validate, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct Message {
     /// The recipient's phone number in E.164 format
     /* #[serde(serialize_with = "serialize_phone_number")] */
     pub number: String,
     /// The content of the message (optional)
-    #[validate(length(min = 1))]
+    #[validate(length(min = 1), custom(function = "validate_not_blank"))]
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub content: Option<String>,
     /// The URL of the media to be sent (optional)
@@ -51,6 +62,49 @@ pub struct Message {
     /// The style of the message delivery (optional)
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub send_style: Option<SendStyle>,
+    /// The time at which the message should be delivered (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub send_at: Option<DateTime<Utc>>,
+    /// Whether to fall back to SMS if the recipient can't receive iMessage (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub allow_sms: Option<bool>,
+    /// The type of message requested from the API (optional)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub message_type: Option<MessageType>,
+    /// Key used to skip sending this message again within the client's idempotency window
+    /// (optional, never sent to the API)
+    #[serde(skip)]
+    pub idempotency_key: Option<String>,
+}
+
+impl Message {
+    /// Creates a message to `number` with every other field unset
+    ///
+    /// Unlike `MessageBuilder`, this doesn't validate anything, so the fields can be set
+    /// directly. A message without content or media is rejected by the API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::{Message, PhoneNumber};
+    ///
+    /// let number: PhoneNumber = "+14155552671".parse().unwrap();
+    /// let mut message = Message::to(number);
+    /// message.content = Some("Hello, world!".into());
+    /// ```
+    pub fn to(number: PhoneNumber) -> Self {
+        Self {
+            number: number.to_e164(),
+            content: None,
+            media_url: None,
+            status_callback: None,
+            send_style: None,
+            send_at: None,
+            allow_sms: None,
+            message_type: None,
+            idempotency_key: None,
+        }
+    }
 }
 
 impl SendableMessage for Message {
@@ -59,12 +113,26 @@ impl SendableMessage for Message {
     }
 
     type ResponseType = MessageResponse;
+
+    fn recipient(&self) -> Option<String> {
+        Some(self.number.clone())
+    }
+
+    fn idempotency_key(&self) -> Option<&str> {
+        self.idempotency_key.as_deref()
+    }
+
+    fn opted_out_recipient(response: &MessageResponse) -> Option<String> {
+        response.opted_out.then(|| response.number.clone())
+    }
 }
 
 /// Response from the Sendblue API after sending a message
 #[serde_as]
 #[skip_serializing_none]
-#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct MessageResponse {
     /// The email of the account
     #[serde(rename = "accountEmail")]
@@ -77,11 +145,11 @@ pub struct MessageResponse {
     /// The status of the message
     pub status: Status,
     /// The error code if any (optional)
-    pub error_code: Option<String>,
+    pub error_code: Option<ErrorCode>,
     /// The error message if any (optional)
     pub error_message: Option<String>,
     /// The handle of the message
-    pub message_handle: String,
+    pub message_handle: MessageHandle,
     /// The date the message was sent
     pub date_sent: DateTime<Utc>,
     /// The date the message was updated
@@ -99,41 +167,95 @@ pub struct MessageResponse {
     pub was_downgraded: Option<bool>,
     /// The plan associated with the message
     pub plan: Option<String>,
-    /// The URL of the media
-    pub media_url: String,
+    /// The URL of the media, `None` if the message has no media
+    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
+    pub media_url: Option<String>,
     /// The type of the message
-    pub message_type: Option<String>,
+    pub message_type: Option<MessageType>,
     /// The group ID associated with the message
-    pub group_id: Option<String>,
+    pub group_id: Option<GroupId>,
     /// The participants in the message
     pub participants: Option<Vec<String>>,
     /// The send style of the message
-    pub send_style: String,
+    #[serde(default)]
+    pub send_style: Option<SendStyle>,
     /// Whether the recipient opted out
     pub opted_out: bool,
     /// The error detail if any (optional)
     pub error_detail: Option<String>,
+    /// Fields this crate doesn't model yet, always empty with the `strict` feature
+    #[cfg_attr(not(feature = "strict"), serde(flatten))]
+    #[cfg_attr(feature = "strict", serde(skip))]
+    pub extra: BTreeMap<String, Value>,
 }
 
-/* #[cfg(feature = "schemars")] */
-/// Meta type for schema generation for MessageResponse
-/* #[derive(Serialize, Deserialize, JsonSchema)]
-pub struct MessageResponseSchema(pub MessageResponse); */
+impl MessageResponse {
+    /// Creates a `MessageBuilder` for replying to the recipient of this message
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sendblue::models::MessageResponse;
+    ///
+    /// # fn reply(response: &MessageResponse) {
+    /// let reply = response
+    ///     .reply_builder()
+    ///     .content("Thanks for your reply!".into())
+    ///     .build()
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn reply_builder(&self) -> MessageBuilder<Message> {
+        MessageBuilder::new(self.number.clone())
+    }
 
-/*  #[cfg(feature = "schemars")]
-impl JsonSchema for MessageResponse {
-    fn schema_name() -> String {
-        "MessageResponse".to_string()
+    /// Returns whether the message was downgraded to SMS
+    ///
+    /// `was_downgraded` is `None` while Sendblue hasn't determined the service yet, which is
+    /// treated the same as not downgraded.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sendblue::models::MessageResponse;
+    ///
+    /// # fn check(response: &MessageResponse) {
+    /// if response.was_downgraded_to_sms() {
+    ///     println!("Message to {} was sent as SMS", response.number);
+    /// }
+    /// # }
+    /// ```
+    pub fn was_downgraded_to_sms(&self) -> bool {
+        self.was_downgraded == Some(true)
     }
 
-    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> Schema {
-        schema_for!(MessageResponse).schema.into()
+    /// Returns the media of the message as a validated `MediaUrl`
+    ///
+    /// Returns `None` if the message has no media or the URL isn't a valid media URL.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sendblue::models::MessageResponse;
+    ///
+    /// # fn show(response: &MessageResponse) {
+    /// if let Some(media) = response.media() {
+    ///     println!("Message contains media: {:?}", media);
+    /// }
+    /// # }
+    /// ```
+    pub fn media(&self) -> Option<MediaUrl> {
+        self.media_url
+            .as_deref()
+            .and_then(|url| MediaUrl::new(url).ok())
     }
-} */
+}
 
 /// Payload for the status callback
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct MessageStatusCallback {
     /// The email of the account
     #[serde(rename = "accountEmail")]
@@ -152,7 +274,7 @@ pub struct MessageStatusCallback {
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub error_message: Option<String>,
     /// The handle of the message
-    pub message_handle: String,
+    pub message_handle: MessageHandle,
     /// The date the message was sent
     pub date_sent: DateTime<Utc>,
     /// The date the message was updated
@@ -177,10 +299,10 @@ pub struct MessageStatusCallback {
     pub media_url: Option<MediaUrl>,
     /// The type of the message
     #[serde(skip_serializing_if = "Option::is_none", default)]
-    pub message_type: Option<String>,
+    pub message_type: Option<MessageType>,
     /// The group ID associated with the message
     #[serde(skip_serializing_if = "Option::is_none", default)]
-    pub group_id: Option<String>,
+    pub group_id: Option<GroupId>,
     /// The participants in the message
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub participants: Option<Vec<String>>,
@@ -194,24 +316,12 @@ pub struct MessageStatusCallback {
     pub error_detail: Option<String>,
 }
 
-#[cfg(feature = "schemars")]
-/// Meta type for schema generation for MessageStatusCallback
-#[derive(Serialize, Deserialize, JsonSchema)]
-pub struct MessageStatusCallbackSchema(pub MessageStatusCallback);
-
-#[cfg(feature = "schemars")]
-impl JsonSchema for MessageStatusCallback {
-    fn schema_name() -> String {
-        "MessageStatusCallback".to_string()
-    }
-
-    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> Schema {
-        schema_for!(MessageStatusCallbackSchema).schema.into()
-    }
-}
-
 /// Request parameters for getting messages
 ///
+/// Sendblue does not filter by status on the server, so `status` is never sent
+/// as a query parameter. When set, the client drops non-matching messages after
+/// the page is fetched, which means a page may hold fewer than `limit` entries.
+///
 /// # Examples
 ///
 /// ```
@@ -219,36 +329,56 @@ impl JsonSchema for MessageStatusCallback {
 ///
 /// let params = GetMessagesParams {
 ///     cid: Some("contact_id".into()),
-///     number: Some(phonenumber::parse(None, "+1234567890").unwrap()),
+///     number: Some("+14155552671".into()),
 ///     limit: Some(50),
 ///     offset: Some(0),
 ///     from_date: Some("2023-06-15 12:00:00".into()),
+///     cursor: None,
+///     status: None,
 /// };
 /// ```
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct GetMessagesParams {
-    pub cid: Option<String>,
-    /* #[serde(deserialize_with = "deserialize_option_phone_number")] */
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cid: Option<ContactId>,
+    /// Serialized in E.164 format
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        serialize_with = "serialize_option_e164"
+    )]
     pub number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
     pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
     pub offset: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
     pub from_date: Option<String>, // or use a more specific date type
+    /// Continue after the page that returned this cursor, see `GetMessagesResponse::next_cursor`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cursor: Option<String>,
+    /// Only keep messages with this status (filtered client-side)
+    #[serde(skip)]
+    pub status: Option<Status>,
 }
 
 /// Message retrieved from the Sendblue API
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct RetrievedMessage {
     /// The date the message was sent
     pub date: String,
     /// Whether SMS is allowed
-    #[serde(rename = "allowSMS")]
+    #[serde(alias = "allowSMS")]
     pub allow_sms: Option<bool>,
     /// The style of the message
-    #[serde(rename = "sendStyle")]
+    #[serde(alias = "sendStyle")]
     pub send_style: Option<String>,
     /// The type of the message
-    #[serde(rename = "type")]
-    pub message_type: String,
+    #[serde(alias = "type")]
+    pub message_type: MessageType,
     /// The unique ID of the message
     pub uuid: String,
     /// The URL to a media attachment
@@ -266,7 +396,7 @@ pub struct RetrievedMessage {
     /// Whether the message was downgraded
     pub was_downgraded: Option<bool>,
     /// The callback URL for status updates
-    #[serde(rename = "callbackURL")]
+    #[serde(alias = "callbackURL")]
     pub callback_url: Option<String>,
     /// The row ID of the message
     pub row_id: Option<String>,
@@ -278,28 +408,195 @@ pub struct RetrievedMessage {
     /* #[serde(deserialize_with = "deserialize_option_phone_number")] */
     pub to_number: Option<String>,
     /// The date the message was sent
-    pub date_sent: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub date_sent: Option<FirestoreTimestamp>,
     /// The date the message was updated
-    pub date_updated: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub date_updated: Option<FirestoreTimestamp>,
     /// Additional error details, if any
     pub error_detail: Option<String>,
     /// The phone ID
     #[serde(rename = "phoneID")]
     pub phone_id: Option<String>,
     /// The group ID associated with the message
-    pub group_id: Option<String>,
+    pub group_id: Option<GroupId>,
     /// The sender's phone number
     /* #[serde(deserialize_with = "deserialize_option_phone_number")] */
     pub from_number: Option<String>,
     /// The error code, if any
-    pub error_code: Option<i32>,
+    pub error_code: Option<ErrorCode>,
+    /// The ID of the contact the message belongs to, if the API reports it
+    #[serde(default)]
+    pub cid: Option<ContactId>,
+    /// Fields this crate doesn't model yet, always empty with the `strict` feature
+    #[cfg_attr(not(feature = "strict"), serde(flatten))]
+    #[cfg_attr(feature = "strict", serde(skip))]
+    pub extra: BTreeMap<String, Value>,
+}
+
+impl From<MessageResponse> for RetrievedMessage {
+    /// Converts the response to a just-sent message into the shape of fetched history
+    ///
+    /// `uuid` is taken from `message_handle` and `date` from `date_sent`. Empty `content`
+    /// becomes `None`. Fields the send response doesn't carry are defaulted:
+    /// `allow_sms`, `callback_url`, `row_id`, `phone_id` and `cid` are `None`, and a missing
+    /// `message_type` becomes `MessageType::Message`. Unmodeled fields are kept in `extra`.
+    fn from(response: MessageResponse) -> Self {
+        let non_empty = |value: String| (!value.is_empty()).then_some(value);
+
+        Self {
+            date: response
+                .date_sent
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            allow_sms: None,
+            send_style: response.send_style.map(|style| style.to_string()),
+            message_type: response.message_type.unwrap_or(MessageType::Message),
+            uuid: response.message_handle.as_str().to_owned(),
+            media_url: response.media_url,
+            content: non_empty(response.content),
+            number: Some(response.number),
+            is_outbound: response.is_outbound,
+            account_email: response.account_email,
+            was_downgraded: response.was_downgraded,
+            callback_url: None,
+            row_id: None,
+            status: response.status,
+            error_message: response.error_message,
+            to_number: Some(response.to_number),
+            date_sent: Some(response.date_sent.into()),
+            date_updated: Some(response.date_updated.into()),
+            error_detail: response.error_detail,
+            phone_id: None,
+            group_id: response.group_id,
+            from_number: Some(response.from_number),
+            error_code: response.error_code,
+            cid: None,
+            extra: response.extra,
+        }
+    }
+}
+
+/// Deserializes an optional string, treating an empty string like `null`
+fn deserialize_empty_string_as_none<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?.filter(|value| !value.is_empty()))
+}
+
+impl RetrievedMessage {
+    /// Creates a `MessageBuilder` for replying to the other party of this message
+    ///
+    /// For outbound messages the reply goes to the recipient, for inbound messages to the
+    /// sender. Returns `None` if the relevant phone number is missing.
+    pub fn reply_builder(&self) -> Option<MessageBuilder<Message>> {
+        self.other_party_number().cloned().map(MessageBuilder::new)
+    }
+
+    /// Returns the phone number of the other party: the recipient of outbound messages, the
+    /// sender of inbound ones
+    fn other_party_number(&self) -> Option<&String> {
+        if self.is_outbound {
+            self.to_number.as_ref().or(self.number.as_ref())
+        } else {
+            self.from_number.as_ref()
+        }
+    }
 }
 
 /// Response from the Sendblue API for getting messages
+///
+/// The response dereferences to a slice of the retrieved messages, so it can be iterated,
+/// indexed and measured directly.
+///
+/// Sendblue doesn't report the total number of matching messages, use `has_more` to decide
+/// whether to fetch another page.
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct GetMessagesResponse {
     /// List of messages retrieved
     pub messages: Vec<RetrievedMessage>,
+    /// Cursor of the next page, if the API paginates with cursors
+    ///
+    /// Pass it as `GetMessagesParams::cursor` to fetch the next page. When it's missing, page
+    /// with `offset` instead.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub next_cursor: Option<String>,
+}
+
+impl GetMessagesResponse {
+    /// Returns true if the page is full, so another page may hold more messages
+    ///
+    /// Pass the `limit` the page was requested with. With a `status` filter in the params the
+    /// page is filtered after it was fetched, so a short page doesn't mean there are no more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::GetMessagesResponse;
+    ///
+    /// let response = GetMessagesResponse { messages: Vec::new(), next_cursor: None };
+    /// assert!(!response.has_more(100));
+    /// ```
+    pub fn has_more(&self, limit: u32) -> bool {
+        limit > 0 && self.messages.len() >= limit as usize
+    }
+
+    /// Groups the messages by the phone number of the other party
+    ///
+    /// Outbound messages are keyed by their recipient, inbound messages by their sender.
+    /// Messages without a parseable number for the other party are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sendblue::models::GetMessagesResponse;
+    ///
+    /// # fn show(response: &GetMessagesResponse) {
+    /// for (number, messages) in response.group_by_number() {
+    ///     println!("{}: {} messages", number, messages.len());
+    /// }
+    /// # }
+    /// ```
+    pub fn group_by_number(&self) -> HashMap<PhoneNumber, Vec<&RetrievedMessage>> {
+        let mut groups: HashMap<PhoneNumber, Vec<&RetrievedMessage>> = HashMap::new();
+        for message in &self.messages {
+            let number = message
+                .other_party_number()
+                .and_then(|number| PhoneNumber::new(number).ok());
+            if let Some(number) = number {
+                groups.entry(number).or_default().push(message);
+            }
+        }
+        groups
+    }
+}
+
+impl Deref for GetMessagesResponse {
+    type Target = [RetrievedMessage];
+
+    fn deref(&self) -> &Self::Target {
+        &self.messages
+    }
+}
+
+impl IntoIterator for GetMessagesResponse {
+    type Item = RetrievedMessage;
+    type IntoIter = std::vec::IntoIter<RetrievedMessage>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.messages.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a GetMessagesResponse {
+    type Item = &'a RetrievedMessage;
+    type IntoIter = std::slice::Iter<'a, RetrievedMessage>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.messages.iter()
+    }
 }
 
 /// Group message request payload
@@ -313,33 +610,124 @@ pub struct GetMessagesResponse {
 /// use sendblue::traits::Url;
 ///
 /// let request = GroupMessage {
-///     numbers: Some(vec![phonenumber::parse(None, "+19998887777").unwrap(), phonenumber::parse(None, "+17778889999").unwrap()]),
+///     numbers: Some(vec!["+16468528190".into(), "+12125551234".into()]),
 ///     group_id: None,
 ///     content: Some("Hello group!".into()),
-///     media_url: Some(MediaUrl::new("https://picsum.photos/200/300.jpg").unwrap()),
+///     media_urls: vec![MediaUrl::new("https://picsum.photos/200/300.jpg").unwrap()],
 ///     send_style: None,
 ///     status_callback: Some(CallbackUrl::new("https://example.com/message-status/1234abcd").unwrap()),
+///     send_at: None,
+///     allow_sms: None,
+///     message_type: None,
+///     idempotency_key: None,
 /// };
 /// ```
-#[derive(Serialize, Deserialize, Validate, Debug)]
+#[derive(Serialize, Deserialize, Validate, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct GroupMessage {
     /// An array of E.164-formatted phone numbers of the desired recipients in a group chat.
     /* #[serde(deserialize_with = "deserialize_option_vec_phone_number")] */
     pub numbers: Option<Vec<String>>,
     /// The group ID to message an existing group.
-    pub group_id: Option<String>,
+    pub group_id: Option<GroupId>,
     /// The content of the message.
-    #[validate(length(min = 1))]
+    #[validate(length(min = 1), custom(function = "validate_not_blank"))]
     pub content: Option<String>,
-    /// A URL to a media file to send to the group.
-    #[serde(skip_serializing_if = "Option::is_none", default)]
-    pub media_url: Option<MediaUrl>,
+    /// URLs of media files to send to the group.
+    ///
+    /// A single attachment is sent as a plain `media_url` string, multiple attachments as an array.
+    #[serde(
+        rename = "media_url",
+        with = "media_urls",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Vec<MediaUrl>"))]
+    pub media_urls: Vec<MediaUrl>,
     /// The style of delivery of the message.
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub send_style: Option<SendStyle>,
     /// An endpoint to notify your app of status updates for this message.
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub status_callback: Option<CallbackUrl>,
+    /// The time at which the message should be delivered.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub send_at: Option<DateTime<Utc>>,
+    /// Whether to fall back to SMS if a recipient can't receive iMessage.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub allow_sms: Option<bool>,
+    /// The type of message requested from the API.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub message_type: Option<MessageType>,
+    /// Key used to skip sending this message again within the client's idempotency window,
+    /// never sent to the API.
+    #[serde(skip)]
+    pub idempotency_key: Option<String>,
+}
+
+impl GroupMessage {
+    /// Creates a group message with every field unset
+    ///
+    /// Unlike `MessageBuilder::new_group`, this doesn't validate anything, so the fields can be
+    /// set directly. Either `numbers` or `group_id` and either content or media must be set
+    /// before sending.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::GroupMessage;
+    ///
+    /// let mut message = GroupMessage::empty();
+    /// message.group_id = Some("66e3b90d-4447-43c6-9439-15a69408ac2".into());
+    /// message.content = Some("Hello, group!".into());
+    /// ```
+    pub fn empty() -> Self {
+        Self {
+            numbers: None,
+            group_id: None,
+            content: None,
+            media_urls: Vec::new(),
+            send_style: None,
+            status_callback: None,
+            send_at: None,
+            allow_sms: None,
+            message_type: None,
+            idempotency_key: None,
+        }
+    }
+}
+
+/// (De)serializes the group message attachments as a single URL or an array of URLs
+mod media_urls {
+    use crate::models::MediaUrl;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(MediaUrl),
+        Many(Vec<MediaUrl>),
+    }
+
+    pub fn serialize<S>(media_urls: &[MediaUrl], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match media_urls {
+            [media_url] => media_url.serialize(serializer),
+            media_urls => media_urls.serialize(serializer),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<MediaUrl>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match OneOrMany::deserialize(deserializer)? {
+            OneOrMany::One(media_url) => Ok(vec![media_url]),
+            OneOrMany::Many(media_urls) => Ok(media_urls),
+        }
+    }
 }
 
 impl SendableMessage for GroupMessage {
@@ -348,11 +736,24 @@ impl SendableMessage for GroupMessage {
     }
 
     type ResponseType = GroupMessageResponse;
+
+    fn recipient(&self) -> Option<String> {
+        match (&self.numbers, &self.group_id) {
+            (Some(numbers), _) if !numbers.is_empty() => Some(numbers.join(",")),
+            (_, Some(group_id)) => Some(group_id.to_string()),
+            _ => None,
+        }
+    }
+    fn idempotency_key(&self) -> Option<&str> {
+        self.idempotency_key.as_deref()
+    }
 }
 
 /// Response from the Sendblue API for sending a group message
 #[skip_serializing_none]
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct GroupMessageResponse {
     /// The email of the account
     #[serde(rename = "accountEmail")]
@@ -364,11 +765,11 @@ pub struct GroupMessageResponse {
     /// The status of the message
     pub status: Status,
     /// The error code, if any
-    pub error_code: Option<i32>,
+    pub error_code: Option<ErrorCode>,
     /// The error message, if any
     pub error_message: Option<String>,
     /// The message handle
-    pub message_handle: String,
+    pub message_handle: MessageHandle,
     /// The date the message was sent
     pub date_sent: DateTime<Utc>,
     /// The date the message was updated
@@ -386,18 +787,121 @@ pub struct GroupMessageResponse {
     pub was_downgraded: Option<bool>,
     /// The plan of the message
     pub plan: String,
-    /// The URL to the media
-    pub media_url: String,
+    /// The URL to the media, `None` if the message has no media
+    #[serde(default, deserialize_with = "deserialize_empty_string_as_none")]
+    pub media_url: Option<String>,
     /// The type of the message
-    pub message_type: String,
+    pub message_type: MessageType,
     /// The group ID
-    pub group_id: String,
+    pub group_id: GroupId,
+    /// The status of each recipient, empty if the API doesn't report them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recipients: Vec<RecipientStatus>,
+    /// Fields this crate doesn't model yet, always empty with the `strict` feature
+    #[cfg_attr(not(feature = "strict"), serde(flatten))]
+    #[cfg_attr(feature = "strict", serde(skip))]
+    pub extra: BTreeMap<String, Value>,
+}
+
+/// Delivery status of a single recipient of a group message
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct RecipientStatus {
+    /// The recipient's phone number, kept as reported, see `GroupMessageResponse::unparsed_numbers`
+    pub number: String,
+    /// The status of the message for this recipient
+    pub status: Status,
+    /// The error message, if sending to this recipient failed
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub error: Option<String>,
+}
+
+impl GroupMessageResponse {
+    /// Returns the recipients the message failed to send to
+    ///
+    /// Only recipients reported in `recipients` are considered, so the result is empty if
+    /// the API didn't report per-recipient statuses.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sendblue::models::GroupMessageResponse;
+    ///
+    /// # fn retry(response: &GroupMessageResponse) {
+    /// for number in response.failed_recipients() {
+    ///     println!("Failed to send to {}", number);
+    /// }
+    /// # }
+    /// ```
+    pub fn failed_recipients(&self) -> Vec<&str> {
+        self.recipients
+            .iter()
+            .filter(|recipient| recipient.status.is_error())
+            .map(|recipient| recipient.number.as_str())
+            .collect()
+    }
+
+    /// Returns the recipients in `number`, `to_number` and `recipients` that aren't valid phone
+    /// numbers
+    ///
+    /// `number`, `to_number` and `recipients` keep every number exactly as the API reported it,
+    /// so a malformed entry never fails decoding the whole response and no raw value is lost.
+    /// That's why this is a method over those fields rather than a separate `unparsed_numbers`
+    /// field: a copy of the failed entries would only duplicate them and could go stale when the
+    /// lists are changed. Each entry is listed once, in the order it first appears.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sendblue::models::GroupMessageResponse;
+    ///
+    /// # fn check(response: &GroupMessageResponse) {
+    /// for number in response.unparsed_numbers() {
+    ///     eprintln!("Sendblue reported an invalid recipient: {}", number);
+    /// }
+    /// # }
+    /// ```
+    pub fn unparsed_numbers(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        self.number
+            .iter()
+            .chain(&self.to_number)
+            .chain(self.recipients.iter().map(|recipient| &recipient.number))
+            .map(String::as_str)
+            .filter(|number| number.parse::<PhoneNumber>().is_err())
+            .filter(|number| seen.insert(*number))
+            .collect()
+    }
+
+    /// Creates a `MessageBuilder` for replying to the same group
+    ///
+    /// The builder carries the `group_id` forward, so no numbers need to be provided.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sendblue::models::GroupMessageResponse;
+    ///
+    /// # fn reply(response: &GroupMessageResponse) {
+    /// let reply = response
+    ///     .reply_builder()
+    ///     .content("Hello again, group!".into())
+    ///     .build()
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn reply_builder(&self) -> MessageBuilder<GroupMessage> {
+        MessageBuilder::new_group().group_id(self.group_id.clone())
+    }
 }
 
 /// Generic builder for creating a `Message` or `GroupMessage`
 pub struct MessageBuilder<T> {
     message: Option<Message>,
     group_message: Option<GroupMessage>,
+    /// Recipients that could not be converted to a `PhoneNumber`, reported by `build()`
+    invalid_numbers: Vec<String>,
     _marker: std::marker::PhantomData<T>,
 }
 
@@ -406,16 +910,30 @@ impl MessageBuilder<Message> {
     ///
     /// # Arguments
     ///
-    /// * `number` - The recipient's phone number in E.164 format
+    /// * `number` - The recipient's phone number, anything convertible to a `PhoneNumber` such
+    ///   as an E.164 string. An invalid number is reported by `build()`.
     ///
     /// # Examples
     ///
     /// ```
     /// use sendblue::models::MessageBuilder;
     ///
-    /// let builder = MessageBuilder::new(phonenumber::parse(None, "+1234567890").unwrap());
+    /// let builder = MessageBuilder::new("+14155552671");
     /// ```
-    pub fn new(number: String) -> Self {
+    pub fn new<N>(number: N) -> Self
+    where
+        N: TryInto<PhoneNumber>,
+        N::Error: fmt::Display,
+    {
+        let mut invalid_numbers = Vec::new();
+        let number = match number.try_into() {
+            Ok(number) => number.to_e164(),
+            Err(e) => {
+                invalid_numbers.push(e.to_string());
+                String::new()
+            }
+        };
+
         Self {
             message: Some(Message {
                 number,
@@ -423,8 +941,44 @@ impl MessageBuilder<Message> {
                 media_url: None,
                 status_callback: None,
                 send_style: None,
+                send_at: None,
+                allow_sms: None,
+                message_type: None,
+                idempotency_key: None,
             }),
             group_message: None,
+            invalid_numbers,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Creates a copy of this builder with every set field kept but the recipient replaced
+    ///
+    /// Use this to fill in a template once and build the same message for many recipients.
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The recipient of the new message
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::{MessageBuilder, PhoneNumber};
+    ///
+    /// let template = MessageBuilder::new("+14155552671").content("Hello, world!".into());
+    ///
+    /// let number: PhoneNumber = "+12125551234".parse().unwrap();
+    /// let message = template.clone_for(number).build().unwrap();
+    /// assert_eq!(message.number, "+12125551234");
+    /// ```
+    pub fn clone_for(&self, number: PhoneNumber) -> MessageBuilder<Message> {
+        Self {
+            message: self.message.clone().map(|message| Message {
+                number: number.to_e164(),
+                ..message
+            }),
+            group_message: None,
+            invalid_numbers: Vec::new(),
             _marker: std::marker::PhantomData,
         }
     }
@@ -440,7 +994,7 @@ impl MessageBuilder<Message> {
     /// ```
     /// use sendblue::models::MessageBuilder;
     ///
-    /// let builder = MessageBuilder::new(phonenumber::parse(None, "+1234567890").unwrap())
+    /// let builder = MessageBuilder::new("+14155552671")
     ///     .content("Hello, world!".into());
     /// ```
     pub fn content(mut self, content: String) -> Self {
@@ -462,7 +1016,7 @@ impl MessageBuilder<Message> {
     /// use sendblue::models::{MessageBuilder, MediaUrl};
     /// use sendblue::traits::Url;
     ///
-    /// let builder = MessageBuilder::new(phonenumber::parse(None, "+1234567890").unwrap())
+    /// let builder = MessageBuilder::new("+14155552671")
     ///     .media_url(MediaUrl::new("https://example.com/media.jpg").unwrap());
     /// ```
     pub fn media_url(mut self, media_url: MediaUrl) -> Self {
@@ -484,7 +1038,7 @@ impl MessageBuilder<Message> {
     /// use sendblue::models::{MessageBuilder, CallbackUrl};
     /// use sendblue::traits::Url;
     ///
-    /// let builder = MessageBuilder::new(phonenumber::parse(None, "+1234567890").unwrap())
+    /// let builder = MessageBuilder::new("+14155552671")
     ///     .status_callback(CallbackUrl::new("https://example.com/message-status/1234abcd").unwrap());
     /// ```
     pub fn status_callback(mut self, status_callback: CallbackUrl) -> Self {
@@ -505,7 +1059,7 @@ impl MessageBuilder<Message> {
     /// ```
     /// use sendblue::models::{MessageBuilder, SendStyle};
     ///
-    /// let builder = MessageBuilder::new(phonenumber::parse(None, "+1234567890").unwrap())
+    /// let builder = MessageBuilder::new("+14155552671")
     ///     .send_style(SendStyle::Invisible);
     /// ```
     pub fn send_style(mut self, send_style: SendStyle) -> Self {
@@ -515,8 +1069,129 @@ impl MessageBuilder<Message> {
         self
     }
 
+    /// Sets the style of delivery of the message from a raw style name
+    ///
+    /// Use this for effects that `SendStyle` doesn't have a variant for yet; the name is sent
+    /// verbatim. Prefer `send_style` whenever a typed variant exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `send_style` - The raw name of the style, e.g. `"slam"`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::MessageBuilder;
+    ///
+    /// let builder = MessageBuilder::new("+14155552671")
+    ///     .send_style_raw("spin");
+    /// ```
+    pub fn send_style_raw(self, send_style: &str) -> Self {
+        self.send_style(SendStyle::from(send_style))
+    }
+
+    /// Schedules the message for delivery at a future time
+    ///
+    /// # Arguments
+    ///
+    /// * `send_at` - The time at which the message should be delivered, must be in the future
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{Duration, Utc};
+    /// use sendblue::models::MessageBuilder;
+    ///
+    /// let builder = MessageBuilder::new("+14155552671")
+    ///     .send_at(Utc::now() + Duration::hours(1));
+    /// ```
+    pub fn send_at(mut self, send_at: DateTime<Utc>) -> Self {
+        if let Some(ref mut msg) = self.message {
+            msg.send_at = Some(send_at);
+        }
+        self
+    }
+
+    /// Sets whether the message may fall back to SMS
+    ///
+    /// Without this, a message to a recipient that can't receive iMessage may fail instead
+    /// of being downgraded.
+    ///
+    /// # Arguments
+    ///
+    /// * `allow_sms` - Whether SMS fallback is allowed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::MessageBuilder;
+    ///
+    /// let builder = MessageBuilder::new("+14155552671")
+    ///     .allow_sms(true);
+    /// ```
+    pub fn allow_sms(mut self, allow_sms: bool) -> Self {
+        if let Some(ref mut msg) = self.message {
+            msg.allow_sms = Some(allow_sms);
+        }
+        self
+    }
+
+    /// Requests a specific type of message instead of leaving it to the API
+    ///
+    /// # Arguments
+    ///
+    /// * `message_type` - The type of message to send
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::{MessageBuilder, MessageType};
+    ///
+    /// let builder = MessageBuilder::new("+14155552671")
+    ///     .message_type(MessageType::Message);
+    /// ```
+    pub fn message_type(mut self, message_type: MessageType) -> Self {
+        if let Some(ref mut msg) = self.message {
+            msg.message_type = Some(message_type);
+        }
+        self
+    }
+
+    /// Sets an idempotency key for the message
+    ///
+    /// Sendblue doesn't deduplicate sends, so the client does: sending a message with the same
+    /// key and payload again within the client's idempotency window (5 minutes by default)
+    /// returns the first response instead of sending it twice. Concurrent sends wait for the
+    /// first one and share its response.
+    ///
+    /// Only successful sends are remembered. A send that failed, e.g. with a timeout after
+    /// Sendblue had already accepted the message, is sent again on retry, so this doesn't
+    /// protect against duplicates caused by network errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `idempotency_key` - A key unique to this message, e.g. a UUID
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::MessageBuilder;
+    ///
+    /// let builder = MessageBuilder::new("+14155552671")
+    ///     .idempotency_key("order-1234-confirmation".into());
+    /// ```
+    pub fn idempotency_key(mut self, idempotency_key: String) -> Self {
+        if let Some(ref mut msg) = self.message {
+            msg.idempotency_key = Some(idempotency_key);
+        }
+        self
+    }
+
     /// Builds the `Message`
     ///
+    /// The recipient must be a valid phone number in E.164 format. The message may carry
+    /// content, media or both, but not neither.
+    ///
     /// # Returns
     ///
     /// * `Result<Message, ValidationError>` - The constructed `Message` object or a `ValidationError`
@@ -526,15 +1201,22 @@ impl MessageBuilder<Message> {
     /// ```
     /// use sendblue::models::MessageBuilder;
     ///
-    /// let message = MessageBuilder::new(phonenumber::parse(None, "+1234567890").unwrap())
+    /// let message = MessageBuilder::new("+14155552671")
     ///     .content("Hello, world!".into())
     ///     .build()
     ///     .unwrap();
     /// ```
     pub fn build(self) -> Result<Message, SendblueError> {
+        check_invalid_numbers(&self.invalid_numbers)?;
         if let Some(msg) = self.message {
-            msg.validate()
-                .map_err(|e| SendblueError::ValidationError(e.to_string()))?;
+            if msg.content.is_none() && msg.media_url.is_none() {
+                return Err(SendblueError::ValidationError(
+                    "Either content or media_url must be provided".into(),
+                ));
+            }
+            msg.validate()?;
+            validate_number(&msg.number)?;
+            validate_send_at(msg.send_at.as_ref())?;
             Ok(msg)
         } else {
             Err(SendblueError::ValidationError(
@@ -557,14 +1239,8 @@ impl MessageBuilder<GroupMessage> {
     pub fn new_group() -> Self {
         Self {
             message: None,
-            group_message: Some(GroupMessage {
-                numbers: None,
-                group_id: None,
-                content: None,
-                media_url: None,
-                send_style: None,
-                status_callback: None,
-            }),
+            group_message: Some(GroupMessage::empty()),
+            invalid_numbers: Vec::new(),
             _marker: std::marker::PhantomData,
         }
     }
@@ -573,7 +1249,8 @@ impl MessageBuilder<GroupMessage> {
     ///
     /// # Arguments
     ///
-    /// * `numbers` - An array of E.164-formatted phone numbers
+    /// * `numbers` - The recipients' phone numbers, anything convertible to a `PhoneNumber`
+    ///   such as E.164 strings. Invalid numbers are reported by `build()`.
     ///
     /// # Examples
     ///
@@ -581,11 +1258,23 @@ impl MessageBuilder<GroupMessage> {
     /// use sendblue::models::MessageBuilder;
     ///
     /// let builder = MessageBuilder::new_group()
-    ///     .numbers(vec![phonenumber::parse(None, "+19998887777").unwrap(), phonenumber::parse(None, "+17778889999").unwrap()]);
+    ///     .numbers(["+16468528190", "+12125551234"]);
     /// ```
-    pub fn numbers(mut self, numbers: Vec<String>) -> Self {
+    pub fn numbers<I, N>(mut self, numbers: I) -> Self
+    where
+        I: IntoIterator<Item = N>,
+        N: TryInto<PhoneNumber>,
+        N::Error: fmt::Display,
+    {
+        let mut parsed = Vec::new();
+        for number in numbers {
+            match number.try_into() {
+                Ok(number) => parsed.push(number.to_e164()),
+                Err(e) => self.invalid_numbers.push(e.to_string()),
+            }
+        }
         if let Some(ref mut grp_msg) = self.group_message {
-            grp_msg.numbers = Some(numbers);
+            grp_msg.numbers = Some(parsed);
         }
         self
     }
@@ -604,7 +1293,7 @@ impl MessageBuilder<GroupMessage> {
     /// let builder = MessageBuilder::new_group()
     ///     .group_id("group_id".into());
     /// ```
-    pub fn group_id(mut self, group_id: String) -> Self {
+    pub fn group_id(mut self, group_id: GroupId) -> Self {
         if let Some(ref mut grp_msg) = self.group_message {
             grp_msg.group_id = Some(group_id);
         }
@@ -632,7 +1321,9 @@ impl MessageBuilder<GroupMessage> {
         self
     }
 
-    /// Sets the media URL for the group message
+    /// Adds a media URL to the group message
+    ///
+    /// Can be called multiple times to send several attachments.
     ///
     /// # Arguments
     ///
@@ -649,7 +1340,31 @@ impl MessageBuilder<GroupMessage> {
     /// ```
     pub fn media_url(mut self, media_url: MediaUrl) -> Self {
         if let Some(ref mut grp_msg) = self.group_message {
-            grp_msg.media_url = Some(media_url);
+            grp_msg.media_urls.push(media_url);
+        }
+        self
+    }
+
+    /// Adds multiple media URLs to the group message
+    ///
+    /// # Arguments
+    ///
+    /// * `media_urls` - The URLs of the media to be sent to the group
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::{MessageBuilder, MediaUrl};
+    /// use sendblue::traits::Url;
+    ///
+    /// let builder = MessageBuilder::new_group().media_urls(vec![
+    ///     MediaUrl::new("https://example.com/first.jpg").unwrap(),
+    ///     MediaUrl::new("https://example.com/second.jpg").unwrap(),
+    /// ]);
+    /// ```
+    pub fn media_urls(mut self, media_urls: impl IntoIterator<Item = MediaUrl>) -> Self {
+        if let Some(ref mut grp_msg) = self.group_message {
+            grp_msg.media_urls.extend(media_urls);
         }
         self
     }
@@ -697,8 +1412,129 @@ impl MessageBuilder<GroupMessage> {
         self
     }
 
+    /// Sets the style of delivery of the group message from a raw style name
+    ///
+    /// Use this for effects that `SendStyle` doesn't have a variant for yet; the name is sent
+    /// verbatim. Prefer `send_style` whenever a typed variant exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `send_style` - The raw name of the style, e.g. `"slam"`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::MessageBuilder;
+    ///
+    /// let builder = MessageBuilder::new_group()
+    ///     .send_style_raw("spin");
+    /// ```
+    pub fn send_style_raw(self, send_style: &str) -> Self {
+        self.send_style(SendStyle::from(send_style))
+    }
+
+    /// Schedules the group message for delivery at a future time
+    ///
+    /// # Arguments
+    ///
+    /// * `send_at` - The time at which the group message should be delivered, must be in the future
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{Duration, Utc};
+    /// use sendblue::models::MessageBuilder;
+    ///
+    /// let builder = MessageBuilder::new_group()
+    ///     .send_at(Utc::now() + Duration::hours(1));
+    /// ```
+    pub fn send_at(mut self, send_at: DateTime<Utc>) -> Self {
+        if let Some(ref mut grp_msg) = self.group_message {
+            grp_msg.send_at = Some(send_at);
+        }
+        self
+    }
+
+    /// Sets whether the group message may fall back to SMS
+    ///
+    /// Without this, a group message to a recipient that can't receive iMessage may fail instead
+    /// of being downgraded.
+    ///
+    /// # Arguments
+    ///
+    /// * `allow_sms` - Whether SMS fallback is allowed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::MessageBuilder;
+    ///
+    /// let builder = MessageBuilder::new_group()
+    ///     .allow_sms(true);
+    /// ```
+    pub fn allow_sms(mut self, allow_sms: bool) -> Self {
+        if let Some(ref mut grp_msg) = self.group_message {
+            grp_msg.allow_sms = Some(allow_sms);
+        }
+        self
+    }
+
+    /// Requests a specific type of message instead of leaving it to the API
+    ///
+    /// # Arguments
+    ///
+    /// * `message_type` - The type of message to send
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::{MessageBuilder, MessageType};
+    ///
+    /// let builder = MessageBuilder::new_group()
+    ///     .message_type(MessageType::Group);
+    /// ```
+    pub fn message_type(mut self, message_type: MessageType) -> Self {
+        if let Some(ref mut grp_msg) = self.group_message {
+            grp_msg.message_type = Some(message_type);
+        }
+        self
+    }
+
+    /// Sets an idempotency key for the group message
+    ///
+    /// Sendblue doesn't deduplicate sends, so the client does: sending a group message with the
+    /// same key and payload again within the client's idempotency window (5 minutes by default)
+    /// returns the first response instead of sending it twice. Concurrent sends wait for the
+    /// first one and share its response.
+    ///
+    /// Only successful sends are remembered. A send that failed, e.g. with a timeout after
+    /// Sendblue had already accepted the message, is sent again on retry, so this doesn't
+    /// protect against duplicates caused by network errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `idempotency_key` - A key unique to this group message, e.g. a UUID
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::MessageBuilder;
+    ///
+    /// let builder = MessageBuilder::new_group()
+    ///     .idempotency_key("order-1234-confirmation".into());
+    /// ```
+    pub fn idempotency_key(mut self, idempotency_key: String) -> Self {
+        if let Some(ref mut grp_msg) = self.group_message {
+            grp_msg.idempotency_key = Some(idempotency_key);
+        }
+        self
+    }
+
     /// Builds the `GroupMessage`
     ///
+    /// Duplicate numbers are removed, and at most `MAX_GROUP_PARTICIPANTS` distinct
+    /// recipients are accepted. Every recipient must be a valid phone number in E.164 format.
+    ///
     /// # Returns
     ///
     /// * `Result<GroupMessage, ValidationError>` - The constructed `GroupMessage` object or a `ValidationError`
@@ -709,13 +1545,24 @@ impl MessageBuilder<GroupMessage> {
     /// use sendblue::models::MessageBuilder;
     ///
     /// let group_message = MessageBuilder::new_group()
-    ///     .numbers(vec![phonenumber::parse(None, "+19998887777").unwrap(), phonenumber::parse(None, "+17778889999").unwrap()])
+    ///     .numbers(["+16468528190", "+12125551234"])
     ///     .content("Hello group!".into())
     ///     .build()
     ///     .unwrap();
     /// ```
     pub fn build(self) -> Result<GroupMessage, SendblueError> {
-        if let Some(grp_msg) = self.group_message {
+        check_invalid_numbers(&self.invalid_numbers)?;
+        if let Some(mut grp_msg) = self.group_message {
+            if let Some(numbers) = grp_msg.numbers.as_mut() {
+                dedup_numbers(numbers);
+                if numbers.len() > MAX_GROUP_PARTICIPANTS {
+                    return Err(SendblueError::ValidationError(format!(
+                        "A group message can have at most {} recipients, got {}",
+                        MAX_GROUP_PARTICIPANTS,
+                        numbers.len()
+                    )));
+                }
+            }
             if grp_msg.numbers.as_ref().map_or(true, |ns| ns.is_empty())
                 && grp_msg.group_id.is_none()
             {
@@ -723,14 +1570,16 @@ impl MessageBuilder<GroupMessage> {
                     "Either numbers or group_id must be provided".into(),
                 ));
             }
-            if grp_msg.content.is_none() && grp_msg.media_url.is_none() {
+            if grp_msg.content.is_none() && grp_msg.media_urls.is_empty() {
                 return Err(SendblueError::ValidationError(
                     "Either content or media_url must be provided".into(),
                 ));
             }
-            grp_msg
-                .validate()
-                .map_err(|e| SendblueError::ValidationError(e.to_string()))?;
+            grp_msg.validate()?;
+            for number in grp_msg.numbers.iter().flatten() {
+                validate_number(number)?;
+            }
+            validate_send_at(grp_msg.send_at.as_ref())?;
             Ok(grp_msg)
         } else {
             Err(SendblueError::ValidationError(
@@ -740,6 +1589,53 @@ impl MessageBuilder<GroupMessage> {
     }
 }
 
+/// Removes duplicate recipients, keeping the first occurrence of each number
+fn dedup_numbers(numbers: &mut Vec<String>) {
+    let mut seen = HashSet::new();
+    numbers.retain(|number| seen.insert(number.clone()));
+}
+
+/// Reports the first recipient that could not be converted to a `PhoneNumber`
+fn check_invalid_numbers(invalid_numbers: &[String]) -> Result<(), SendblueError> {
+    match invalid_numbers.first() {
+        Some(error) => Err(SendblueError::ValidationError(format!(
+            "Invalid phone number: {}",
+            error
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Checks that a recipient is a valid phone number in E.164 format
+fn validate_number(number: &str) -> Result<(), SendblueError> {
+    match ::phonenumber::parse(None, number) {
+        Ok(parsed) if ::phonenumber::is_valid(&parsed) => Ok(()),
+        _ => Err(SendblueError::ValidationError(format!(
+            "Invalid phone number: {}",
+            number
+        ))),
+    }
+}
+
+/// Checks that a scheduled delivery time lies in the future
+fn validate_send_at(send_at: Option<&DateTime<Utc>>) -> Result<(), SendblueError> {
+    match send_at {
+        Some(send_at) if *send_at <= Utc::now() => Err(SendblueError::ValidationError(
+            "send_at must be in the future".into(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that content isn't made up of whitespace only
+fn validate_not_blank(content: &str) -> Result<(), validator::ValidationError> {
+    if content.trim().is_empty() {
+        return Err(validator::ValidationError::new("blank")
+            .with_message("content must not be blank".into()));
+    }
+    Ok(())
+}
+
 /// Builder for creating a `GetMessagesParams`
 ///
 /// # Examples
@@ -749,20 +1645,22 @@ impl MessageBuilder<GroupMessage> {
 ///
 /// let params = GetMessagesParamsBuilder::new()
 ///     .cid(Some("contact_id".into()))
-///     .number(Some(phonenumber::parse(None, "+1234567890").unwrap()))
+///     .number(Some("+14155552671".into()))
 ///     .limit(Some(50))
 ///     .offset(Some(0))
 ///     .from_date(Some("2023-06-15 12:00:00".into()))
 ///     .build();
 /// ```
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct GetMessagesParamsBuilder {
-    cid: Option<String>,
+    cid: Option<ContactId>,
     /* #[serde(deserialize_with = "deserialize_option_phone_number")] */
     number: Option<String>,
     limit: Option<u32>,
     offset: Option<u32>,
     from_date: Option<String>,
+    cursor: Option<String>,
+    status: Option<Status>,
 }
 
 impl GetMessagesParamsBuilder {
@@ -773,10 +1671,12 @@ impl GetMessagesParamsBuilder {
             limit: None,
             offset: None,
             from_date: None,
+            cursor: None,
+            status: None,
         }
     }
 
-    pub fn cid(mut self, cid: Option<String>) -> Self {
+    pub fn cid(mut self, cid: Option<ContactId>) -> Self {
         self.cid = cid;
         self
     }
@@ -801,6 +1701,16 @@ impl GetMessagesParamsBuilder {
         self
     }
 
+    pub fn cursor(mut self, cursor: Option<String>) -> Self {
+        self.cursor = cursor;
+        self
+    }
+
+    pub fn status(mut self, status: Option<Status>) -> Self {
+        self.status = status;
+        self
+    }
+
     pub fn build(self) -> GetMessagesParams {
         GetMessagesParams {
             cid: self.cid,
@@ -808,6 +1718,8 @@ impl GetMessagesParamsBuilder {
             limit: self.limit,
             offset: self.offset,
             from_date: self.from_date,
+            cursor: self.cursor,
+            status: self.status,
         }
     }
 }
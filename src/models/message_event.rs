@@ -0,0 +1,55 @@
+//! Message Event Model
+//!
+//! This module provides a summary of a status callback as a single event, for driving state
+//! machines from webhooks.
+
+use crate::models::{ErrorCode, MessageStatusCallback, Status};
+
+/// State of a message as reported by a status callback
+///
+/// `Failed` covers both the `FAILED` and `ERROR` statuses and carries the error details of the
+/// callback.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sendblue::models::{MessageEvent, MessageStatusCallback};
+///
+/// # fn handle(callback: MessageStatusCallback) {
+/// match MessageEvent::from(callback) {
+///     MessageEvent::Delivered | MessageEvent::Read => println!("Message arrived"),
+///     MessageEvent::Failed { error_message, .. } => eprintln!("Message failed: {:?}", error_message),
+///     _ => {}
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MessageEvent {
+    Queued,
+    Sent,
+    Delivered,
+    Read,
+    Received,
+    Failed {
+        /// The error code, if any
+        error_code: Option<ErrorCode>,
+        /// The error message, falling back to the error detail
+        error_message: Option<String>,
+    },
+}
+
+impl From<MessageStatusCallback> for MessageEvent {
+    fn from(callback: MessageStatusCallback) -> Self {
+        match callback.status {
+            Status::Queued => MessageEvent::Queued,
+            Status::Sent => MessageEvent::Sent,
+            Status::Delivered => MessageEvent::Delivered,
+            Status::Read => MessageEvent::Read,
+            Status::Received => MessageEvent::Received,
+            Status::Failed | Status::Error => MessageEvent::Failed {
+                error_code: callback.error_code,
+                error_message: callback.error_message.or(callback.error_detail),
+            },
+        }
+    }
+}
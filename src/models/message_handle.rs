@@ -0,0 +1,56 @@
+//! Message Handle Model
+//!
+//! This module provides the data model for message handles used in the Sendblue API.
+
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, fmt, str::FromStr};
+
+/// Identifier of a sent message, as returned in `message_handle`
+///
+/// # Examples
+///
+/// ```
+/// use sendblue::models::MessageHandle;
+///
+/// let message_handle = MessageHandle::from("dfd747ba-5600-4a8a-804a-a614a0fbc1c5");
+/// assert_eq!(message_handle.as_str(), "dfd747ba-5600-4a8a-804a-a614a0fbc1c5");
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(transparent)]
+pub struct MessageHandle(String);
+
+impl MessageHandle {
+    /// Returns the message handle as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for MessageHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for MessageHandle {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl From<String> for MessageHandle {
+    fn from(message_handle: String) -> Self {
+        Self(message_handle)
+    }
+}
+
+impl From<&str> for MessageHandle {
+    fn from(message_handle: &str) -> Self {
+        Self(message_handle.to_string())
+    }
+}
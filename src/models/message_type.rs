@@ -0,0 +1,54 @@
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Type of a message, either sent to a single recipient or to a group
+///
+/// Types this crate doesn't know about yet are kept as `Unknown` instead of failing to
+/// deserialize.
+///
+/// # Examples
+///
+/// ```
+/// use sendblue::models::MessageType;
+///
+/// let message_type: MessageType = serde_json::from_str("\"group\"").unwrap();
+/// assert_eq!(message_type, MessageType::Group);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum MessageType {
+    Message,
+    Group,
+    #[serde(untagged)]
+    #[cfg_attr(feature = "schemars", schemars(skip))]
+    Unknown(String),
+}
+
+impl MessageType {
+    /// Returns the name of the type as used by the API
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::MessageType;
+    ///
+    /// assert_eq!(MessageType::Message.as_str(), "message");
+    /// ```
+    pub fn as_str(&self) -> &str {
+        match self {
+            MessageType::Message => "message",
+            MessageType::Group => "group",
+            MessageType::Unknown(message_type) => message_type,
+        }
+    }
+}
+
+impl fmt::Display for MessageType {
+    /// Formats the type as its wire value, matching the serialized form
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -3,26 +3,49 @@
 //! This module provides the data models used by the Sendblue API, including messages, URLs,
 //! statuses, and request/response structures for various API endpoints.
 
+pub mod account;
 pub mod callback_url;
+pub mod contact_id;
+pub mod dyn_message;
 pub mod evaluate_service;
+pub mod group_id;
 pub mod media_url;
 pub mod message;
-//pub mod phonenumber;
+pub mod message_event;
+pub mod message_handle;
+pub mod message_type;
+pub mod phonenumber;
+pub mod reaction;
+pub mod response_meta;
 pub mod send_style;
 pub mod status;
+pub mod timestamp;
 pub mod typing_indicator;
 pub mod voice_note;
 
+pub use account::{AccountInfo, AccountUsage};
 pub use callback_url::CallbackUrl;
-pub use evaluate_service::{EvaluateService, EvaluateServiceBuilder, EvaluateServiceResponse};
+pub use contact_id::ContactId;
+pub use dyn_message::{DynMessage, DynMessageResponse};
+pub use evaluate_service::{
+    ContactSegments, EvaluateService, EvaluateServiceBuilder, EvaluateServiceResponse,
+    EvaluateServiceType,
+};
+pub use group_id::GroupId;
 pub use media_url::MediaUrl;
 pub use message::{
     GetMessagesParams, GetMessagesParamsBuilder, GetMessagesResponse, GroupMessage,
     GroupMessageResponse, Message, MessageBuilder, MessageResponse, MessageStatusCallback,
-    RetrievedMessage,
+    RecipientStatus, RetrievedMessage,
 };
+pub use message_event::MessageEvent;
+pub use message_handle::MessageHandle;
+pub use message_type::MessageType;
 pub use phonenumber::PhoneNumber;
+pub use reaction::{Reaction, SendReaction};
+pub use response_meta::ResponseMeta;
 pub use send_style::SendStyle;
 pub use status::{ErrorCode, Status};
+pub use timestamp::FirestoreTimestamp;
 pub use typing_indicator::{TypingIndicator, TypingIndicatorResponse, TypingIndicatorStatus};
 pub use voice_note::VoiceNote;
@@ -1,105 +1,203 @@
-use phonenumber::{parse, Mode, PhoneNumber as RawPhoneNumber};
-use schemars::{gen::SchemaGenerator, schema::{Schema, SchemaObject}, JsonSchema};
-use serde::{Deserialize, Serialize};
-use std::ops::Deref;
-use serde_with::{serde_as, skip_serializing_none, NoneAsEmptyString};
-use chrono::{DateTime, Utc};
-use validator::Validate;
-
-// Newtype um PhoneNumber
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(transparent)]
+//! Phone Number Model
+//!
+//! This module provides the `PhoneNumber` newtype and serde helpers for (de)serializing phone
+//! numbers in E.164 format, the format the Sendblue API uses on the wire.
+
+use phonenumber::{parse, Mode, ParseError, PhoneNumber as RawPhoneNumber};
+#[cfg(feature = "schemars")]
+use schemars::{
+    schema::{InstanceType, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// A phone number, (de)serialized in E.164 format
+///
+/// Wraps `phonenumber::PhoneNumber` so numbers can be parsed straight from strings.
+///
+/// # Examples
+///
+/// ```
+/// use sendblue::models::PhoneNumber;
+///
+/// let number: PhoneNumber = "+14155552671".try_into().unwrap();
+/// assert_eq!(number.to_string(), "+14155552671");
+///
+/// let number: PhoneNumber = "+14155552671".parse().unwrap();
+/// assert_eq!(number, PhoneNumber::new("+14155552671").unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PhoneNumber(RawPhoneNumber);
 
-impl Deref for PhoneNumber {
-    type Target = RawPhoneNumber;
+impl PhoneNumber {
+    /// Parses a phone number given in international format, e.g. `+14155552671`
+    ///
+    /// # Errors
+    ///
+    /// Returns the `ParseError` of the `phonenumber` crate if the number can't be parsed.
+    pub fn new(number: &str) -> Result<Self, ParseError> {
+        parse(None, number).map(Self)
+    }
+
+    /// Returns the number in E.164 format
+    pub fn to_e164(&self) -> String {
+        self.0.format().mode(Mode::E164).to_string()
+    }
 
-    fn deref(&self) -> &Self::Target {
+    /// Returns the wrapped `phonenumber::PhoneNumber`
+    pub fn as_inner(&self) -> &RawPhoneNumber {
         &self.0
     }
+
+    /// Consumes the newtype, returning the wrapped `phonenumber::PhoneNumber`
+    pub fn into_inner(self) -> RawPhoneNumber {
+        self.0
+    }
 }
 
-// Implement JsonSchema for PhoneNumber
-impl JsonSchema for PhoneNumber {
-    fn schema_name() -> String {
-        "PhoneNumber".to_string()
+impl fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_e164())
     }
+}
 
-    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
-        let schema_object = SchemaObject {
-            instance_type: Some(schemars::schema::InstanceType::String.into()),
-            format: Some("phone".to_string()),
-            ..Default::default()
-        };
-        Schema::Object(schema_object)
+impl FromStr for PhoneNumber {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<&str> for PhoneNumber {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<String> for PhoneNumber {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(&value)
+    }
+}
+
+impl TryFrom<&String> for PhoneNumber {
+    type Error = ParseError;
+
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        Self::new(value)
     }
 }
 
 impl From<RawPhoneNumber> for PhoneNumber {
-    fn from(phone_number: RawPhoneNumber) -> Self {
-        PhoneNumber(phone_number)
+    fn from(number: RawPhoneNumber) -> Self {
+        Self(number)
+    }
+}
+
+impl From<PhoneNumber> for RawPhoneNumber {
+    fn from(number: PhoneNumber) -> Self {
+        number.0
     }
 }
 
+impl Serialize for PhoneNumber {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_phone_number(&self.0, serializer)
+    }
+}
 
-/* pub fn deserialize_phone_number<'de, D>(deserializer: D) -> Result<PhoneNumber, D::Error>
+impl<'de> Deserialize<'de> for PhoneNumber {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_phone_number(deserializer).map(Self)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for PhoneNumber {
+    fn schema_name() -> String {
+        "PhoneNumber".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("phone".to_string()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// Serializes a phone number as an E.164 string
+pub fn serialize_phone_number<S>(number: &RawPhoneNumber, serializer: S) -> Result<S::Ok, S::Error>
 where
-    D: Deserializer<'de>,
+    S: Serializer,
 {
-    let s: &str = Deserialize::deserialize(deserializer)?;
-    parse(None, s).map_err(serde::de::Error::custom)
-} */
+    let e164 = number.format().mode(Mode::E164).to_string();
+    serializer.serialize_str(&e164)
+}
 
-/* pub fn deserialize_option_phone_number<'de, D>(
-    deserializer: D,
-) -> Result<Option<PhoneNumber>, D::Error>
+/// Serializes an optional phone number string in E.164 format
+///
+/// Numbers that can't be parsed are serialized unchanged, leaving it to the API to reject them.
+pub fn serialize_option_e164<S>(number: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
 where
-    D: Deserializer<'de>,
+    S: Serializer,
 {
-    let opt: Option<&str> = Option::deserialize(deserializer)?;
-    match opt {
-        Some(s) => parse(None, s).map(Some).map_err(serde::de::Error::custom),
-        None => Ok(None),
+    match number {
+        Some(number) => match parse(None, number) {
+            Ok(parsed) => serialize_phone_number(&parsed, serializer),
+            Err(_) => serializer.serialize_str(number),
+        },
+        None => serializer.serialize_none(),
     }
-} */
+}
 
-/* pub fn deserialize_vec_phone_number<'de, D>(deserializer: D) -> Result<Vec<PhoneNumber>, D::Error>
+/// Deserializes a phone number from an E.164 string
+pub fn deserialize_phone_number<'de, D>(deserializer: D) -> Result<RawPhoneNumber, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let vec: Vec<&str> = Deserialize::deserialize(deserializer)?;
-    vec.into_iter()
-        .map(|s| parse(None, s).map_err(serde::de::Error::custom))
-        .collect()
-} */
+    let s = String::deserialize(deserializer)?;
+    parse(None, &s).map_err(serde::de::Error::custom)
+}
 
-/* pub fn deserialize_option_vec_phone_number<'de, D>(
-    deserializer: D,
-) -> Result<Option<Vec<PhoneNumber>>, D::Error>
+/// Serializes a list of phone numbers as E.164 strings
+pub fn serialize_vec_phone_number<S>(
+    numbers: &[RawPhoneNumber],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
 where
-    D: Deserializer<'de>,
+    S: Serializer,
 {
-    let opt: Option<Vec<&str>> = Option::deserialize(deserializer)?;
-    match opt {
-        Some(vec) => {
-            let mut phone_numbers = Vec::new();
-            for s in vec {
-                match parse(None, s) {
-                    Ok(phone_number) => phone_numbers.push(phone_number),
-                    Err(err) => return Err(serde::de::Error::custom(err)),
-                }
-            }
-            Ok(Some(phone_numbers))
-        }
-        None => Ok(None),
-    }
-} */
+    serializer.collect_seq(
+        numbers
+            .iter()
+            .map(|number| number.format().mode(Mode::E164).to_string()),
+    )
+}
 
-pub fn serialize_phone_number<S>(number: &PhoneNumber, serializer: S) -> Result<S::Ok, S::Error>
+/// Deserializes a list of phone numbers from E.164 strings
+pub fn deserialize_vec_phone_number<'de, D>(
+    deserializer: D,
+) -> Result<Vec<RawPhoneNumber>, D::Error>
 where
-    S: Serializer,
+    D: Deserializer<'de>,
 {
-    let e164 = number.format().mode(Mode::E164).to_string();
-    serializer.serialize_str(&e164)
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|s| parse(None, s).map_err(serde::de::Error::custom))
+        .collect()
 }
-
@@ -0,0 +1,52 @@
+//! Reaction Model
+//!
+//! This module provides the request model for sending tapback reactions used in the Sendblue API.
+
+use crate::models::MessageHandle;
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tapback reaction to an iMessage
+///
+/// # Examples
+///
+/// ```
+/// use sendblue::models::Reaction;
+///
+/// let reaction = Reaction::Love;
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Reaction {
+    Love,
+    Like,
+    Dislike,
+    Laugh,
+    Emphasize,
+    Question,
+}
+
+/// Reaction Request
+///
+/// This struct represents a request to react to a message in the Sendblue API.
+///
+/// # Examples
+///
+/// ```
+/// use sendblue::models::{MessageHandle, Reaction, SendReaction};
+///
+/// let request = SendReaction {
+///     message_handle: MessageHandle::from("dfd747ba-5600-4a8a-804a-a614a0fbc1c5"),
+///     reaction: Reaction::Like,
+/// };
+/// ```
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct SendReaction {
+    /// The handle of the message to react to
+    pub message_handle: MessageHandle,
+    /// The reaction to send
+    pub reaction: Reaction,
+}
@@ -0,0 +1,72 @@
+//! Response Metadata Model
+//!
+//! This module provides the status code and headers returned alongside a successful response,
+//! e.g. to read rate-limit information.
+
+use reqwest::{header::HeaderMap, Response, StatusCode};
+
+/// Status code and headers of a successful Sendblue API response
+///
+/// # Examples
+///
+/// ```
+/// use reqwest::{header::{HeaderMap, HeaderValue}, StatusCode};
+/// use sendblue::models::ResponseMeta;
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("x-ratelimit-remaining", HeaderValue::from_static("42"));
+///
+/// let meta = ResponseMeta::new(StatusCode::ACCEPTED, headers);
+/// assert_eq!(meta.rate_limit_remaining(), Some(42));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    /// The HTTP status code of the response
+    pub status: StatusCode,
+    /// All headers returned with the response
+    pub headers: HeaderMap,
+}
+
+impl ResponseMeta {
+    /// Creates a new `ResponseMeta` from a status code and headers
+    pub fn new(status: StatusCode, headers: HeaderMap) -> Self {
+        Self { status, headers }
+    }
+
+    /// Returns the value of the `X-RateLimit-Limit` header, if present
+    pub fn rate_limit_limit(&self) -> Option<u64> {
+        self.header_u64("x-ratelimit-limit")
+    }
+
+    /// Returns the value of the `X-RateLimit-Remaining` header, if present
+    pub fn rate_limit_remaining(&self) -> Option<u64> {
+        self.header_u64("x-ratelimit-remaining")
+    }
+
+    /// Returns the value of the `X-RateLimit-Reset` header, if present
+    pub fn rate_limit_reset(&self) -> Option<u64> {
+        self.header_u64("x-ratelimit-reset")
+    }
+
+    /// Returns the value of the `X-Request-Id` header, if present
+    ///
+    /// Include it when contacting Sendblue support about a request.
+    pub fn request_id(&self) -> Option<&str> {
+        self.headers
+            .get("x-request-id")
+            .and_then(|value| value.to_str().ok())
+    }
+
+    fn header_u64(&self, name: &str) -> Option<u64> {
+        self.headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+    }
+}
+
+impl From<&Response> for ResponseMeta {
+    fn from(response: &Response) -> Self {
+        Self::new(response.status(), response.headers().clone())
+    }
+}
@@ -1,9 +1,14 @@
 #[cfg(feature = "schemars")]
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Style of the message delivery
 ///
+/// Styles this crate doesn't know about yet are kept as `Unknown` instead of failing to
+/// deserialize. `Unknown` is also serialized verbatim, so it can be used to send effects that
+/// were added to iMessage after this crate's release (see `SendStyle::from`).
+///
 /// # Examples
 ///
 /// ```
@@ -11,7 +16,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// let style = SendStyle::Celebration;
 /// ```
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum SendStyle {
@@ -30,4 +35,128 @@ pub enum SendStyle {
     Slam,
     #[serde(rename = "")]
     Default,
+    #[serde(untagged)]
+    #[cfg_attr(feature = "schemars", schemars(skip))]
+    Unknown(String),
+}
+
+/// Every style with a typed variant, except `Default`
+const ALL: &[SendStyle] = &[
+    SendStyle::Celebration,
+    SendStyle::ShootingStar,
+    SendStyle::Fireworks,
+    SendStyle::Lasers,
+    SendStyle::Love,
+    SendStyle::Confetti,
+    SendStyle::Balloons,
+    SendStyle::Spotlight,
+    SendStyle::Echo,
+    SendStyle::Invisible,
+    SendStyle::Gentle,
+    SendStyle::Loud,
+    SendStyle::Slam,
+];
+
+impl SendStyle {
+    /// Returns every style this crate knows about, except `Default`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::SendStyle;
+    ///
+    /// for style in SendStyle::all() {
+    ///     println!("{}", style.label());
+    /// }
+    /// ```
+    pub fn all() -> &'static [SendStyle] {
+        ALL
+    }
+
+    /// Returns the name of the style as shown in Messages, e.g. `"Shooting Star"`
+    ///
+    /// `Unknown` styles are labelled `"Unknown"`, use `as_str` to get their raw name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::SendStyle;
+    ///
+    /// assert_eq!(SendStyle::Invisible.label(), "Invisible Ink");
+    /// ```
+    pub fn label(&self) -> &'static str {
+        match self {
+            SendStyle::Celebration => "Celebration",
+            SendStyle::ShootingStar => "Shooting Star",
+            SendStyle::Fireworks => "Fireworks",
+            SendStyle::Lasers => "Lasers",
+            SendStyle::Love => "Love",
+            SendStyle::Confetti => "Confetti",
+            SendStyle::Balloons => "Balloons",
+            SendStyle::Spotlight => "Spotlight",
+            SendStyle::Echo => "Echo",
+            SendStyle::Invisible => "Invisible Ink",
+            SendStyle::Gentle => "Gentle",
+            SendStyle::Loud => "Loud",
+            SendStyle::Slam => "Slam",
+            SendStyle::Default => "Default",
+            SendStyle::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// Returns the name of the style as sent to the API, `""` for `Default`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::SendStyle;
+    ///
+    /// assert_eq!(SendStyle::ShootingStar.as_str(), "shooting_star");
+    /// assert_eq!(SendStyle::Default.as_str(), "");
+    /// ```
+    pub fn as_str(&self) -> &str {
+        match self {
+            SendStyle::Celebration => "celebration",
+            SendStyle::ShootingStar => "shooting_star",
+            SendStyle::Fireworks => "fireworks",
+            SendStyle::Lasers => "lasers",
+            SendStyle::Love => "love",
+            SendStyle::Confetti => "confetti",
+            SendStyle::Balloons => "balloons",
+            SendStyle::Spotlight => "spotlight",
+            SendStyle::Echo => "echo",
+            SendStyle::Invisible => "invisible",
+            SendStyle::Gentle => "gentle",
+            SendStyle::Loud => "loud",
+            SendStyle::Slam => "slam",
+            SendStyle::Default => "",
+            SendStyle::Unknown(style) => style,
+        }
+    }
+}
+
+impl fmt::Display for SendStyle {
+    /// Formats the style as its wire value, matching the serialized form
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for SendStyle {
+    /// Converts a raw style name into a `SendStyle`
+    ///
+    /// Known names map to their typed variant, anything else becomes `Unknown`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::SendStyle;
+    ///
+    /// assert_eq!(SendStyle::from("slam"), SendStyle::Slam);
+    /// assert_eq!(SendStyle::from("spin"), SendStyle::Unknown("spin".into()));
+    /// ```
+    fn from(style: &str) -> Self {
+        serde_json::from_value(serde_json::Value::String(style.to_owned()))
+            .unwrap_or_else(|_| SendStyle::Unknown(style.to_owned()))
+    }
 }
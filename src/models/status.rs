@@ -3,13 +3,18 @@
 //! This module provides the data model for message statuses used in the Sendblue API.
 
 #[cfg(feature = "schemars")]
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use schemars::{
+    schema::{InstanceType, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
 
 /// Status of the message in the Sendblue API
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
 pub enum Status {
     Queued,
     Failed,
@@ -17,26 +22,187 @@ pub enum Status {
     Delivered,
     Read,
     Received,
+    Error,
+}
+
+impl Status {
+    /// Returns `true` if the message will not change status anymore
+    ///
+    /// Terminal statuses are `Delivered`, `Read`, `Failed` and `Error`, so callers polling for updates
+    /// can stop once this returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::Status;
+    ///
+    /// assert!(Status::Delivered.is_terminal());
+    /// assert!(!Status::Queued.is_terminal());
+    /// ```
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Status::Delivered | Status::Read | Status::Failed | Status::Error
+        )
+    }
+
+    /// Returns `true` if the message failed to send, reported as `Failed` or `Error`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::Status;
+    ///
+    /// assert!(Status::Failed.is_error());
+    /// assert!(!Status::Sent.is_error());
+    /// ```
+    pub fn is_error(&self) -> bool {
+        matches!(self, Status::Failed | Status::Error)
+    }
 }
 
 /// Error codes returned by the Sendblue API
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+///
+/// The API sends codes either as numbers or as strings, both are accepted when deserializing.
+/// Codes this crate doesn't know about are kept as `Other`, values that aren't a code at all,
+/// e.g. negative numbers, become `Unknown`.
+///
+/// Every code serializes as a number, `Unknown` has no code and serializes as `null`.
+///
+/// # Examples
+///
+/// ```
+/// use sendblue::models::ErrorCode;
+///
+/// let numeric: ErrorCode = serde_json::from_str("4001").unwrap();
+/// let string: ErrorCode = serde_json::from_str("\"4001\"").unwrap();
+/// assert_eq!(numeric, ErrorCode::RateLimitExceeded);
+/// assert_eq!(string, ErrorCode::RateLimitExceeded);
+/// assert_eq!(serde_json::to_string(&numeric).unwrap(), "4001");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum ErrorCode {
-    #[serde(rename = "4000")]
     ValidationError,
-    #[serde(rename = "4001")]
     RateLimitExceeded,
-    #[serde(rename = "4002")]
     BlacklistedNumber,
-    #[serde(rename = "5000")]
     InternalError,
-    #[serde(rename = "5003")]
     ServerRateExceeded,
-    #[serde(rename = "10001")]
     MessageFailedToSend,
-    #[serde(rename = "10002")]
     FailedToResolveMessageStatus,
-    #[serde(other)]
     Unknown,
+    Other(u32),
+}
+
+impl ErrorCode {
+    /// Returns the numeric code, or `None` for `Unknown`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::ErrorCode;
+    ///
+    /// assert_eq!(ErrorCode::ValidationError.code(), Some(4000));
+    /// assert_eq!(ErrorCode::Other(22).code(), Some(22));
+    /// assert_eq!(ErrorCode::Unknown.code(), None);
+    /// ```
+    pub fn code(&self) -> Option<u32> {
+        match self {
+            ErrorCode::ValidationError => Some(4000),
+            ErrorCode::RateLimitExceeded => Some(4001),
+            ErrorCode::BlacklistedNumber => Some(4002),
+            ErrorCode::InternalError => Some(5000),
+            ErrorCode::ServerRateExceeded => Some(5003),
+            ErrorCode::MessageFailedToSend => Some(10001),
+            ErrorCode::FailedToResolveMessageStatus => Some(10002),
+            ErrorCode::Other(code) => Some(*code),
+            ErrorCode::Unknown => None,
+        }
+    }
+
+    /// Creates an `ErrorCode` from its numeric code, keeping codes this crate doesn't know as
+    /// `Other`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sendblue::models::ErrorCode;
+    ///
+    /// assert_eq!(ErrorCode::from_code(4001), ErrorCode::RateLimitExceeded);
+    /// assert_eq!(ErrorCode::from_code(42), ErrorCode::Other(42));
+    /// ```
+    pub fn from_code(code: u32) -> ErrorCode {
+        match code {
+            4000 => ErrorCode::ValidationError,
+            4001 => ErrorCode::RateLimitExceeded,
+            4002 => ErrorCode::BlacklistedNumber,
+            5000 => ErrorCode::InternalError,
+            5003 => ErrorCode::ServerRateExceeded,
+            10001 => ErrorCode::MessageFailedToSend,
+            10002 => ErrorCode::FailedToResolveMessageStatus,
+            code => ErrorCode::Other(code),
+        }
+    }
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.code() {
+            Some(code) => serializer.serialize_u32(code),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorCode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ErrorCodeVisitor;
+
+        impl de::Visitor<'_> for ErrorCodeVisitor {
+            type Value = ErrorCode;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a numeric error code as number or string")
+            }
+
+            fn visit_u64<E: de::Error>(self, code: u64) -> Result<ErrorCode, E> {
+                Ok(u32::try_from(code).map_or(ErrorCode::Unknown, ErrorCode::from_code))
+            }
+
+            fn visit_i64<E: de::Error>(self, code: i64) -> Result<ErrorCode, E> {
+                Ok(u32::try_from(code).map_or(ErrorCode::Unknown, ErrorCode::from_code))
+            }
+
+            fn visit_str<E: de::Error>(self, code: &str) -> Result<ErrorCode, E> {
+                Ok(code
+                    .trim()
+                    .parse()
+                    .map_or(ErrorCode::Unknown, ErrorCode::from_code))
+            }
+        }
+
+        deserializer.deserialize_any(ErrorCodeVisitor)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for ErrorCode {
+    fn schema_name() -> String {
+        "ErrorCode".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(vec![InstanceType::Integer, InstanceType::Null].into()),
+            format: Some("uint32".to_string()),
+            ..Default::default()
+        }
+        .into()
+    }
 }
@@ -0,0 +1,123 @@
+//! Timestamp Model
+//!
+//! This module provides the `FirestoreTimestamp` type for dates the Sendblue API returns as
+//! Firestore timestamp objects.
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "schemars")]
+use schemars::{schema::Schema, JsonSchema};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, ops::Deref};
+
+/// A point in time, (de)serialized as a Firestore timestamp
+///
+/// Deserializes from either a Firestore timestamp object of the form
+/// `{ "_seconds": ..., "_nanoseconds": ... }` or an ISO-8601 string, and always serializes to
+/// the Firestore object. Dereferences to `DateTime<Utc>`.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{DateTime, Utc};
+/// use sendblue::models::FirestoreTimestamp;
+///
+/// let timestamp: FirestoreTimestamp =
+///     serde_json::from_str(r#"{ "_seconds": 1695327725, "_nanoseconds": 66000000 }"#).unwrap();
+/// assert_eq!(timestamp.to_rfc3339(), "2023-09-21T20:22:05.066+00:00");
+///
+/// let date: DateTime<Utc> = timestamp.into();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FirestoreTimestamp(DateTime<Utc>);
+
+/// Wire format of a Firestore timestamp
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "FirestoreTimestamp"))]
+struct RawFirestoreTimestamp {
+    #[serde(rename = "_seconds")]
+    seconds: i64,
+    #[serde(rename = "_nanoseconds")]
+    nanoseconds: u32,
+}
+
+impl FirestoreTimestamp {
+    /// Returns the wrapped date
+    pub fn into_inner(self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+impl Deref for FirestoreTimestamp {
+    type Target = DateTime<Utc>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<DateTime<Utc>> for FirestoreTimestamp {
+    fn from(date: DateTime<Utc>) -> Self {
+        Self(date)
+    }
+}
+
+impl From<FirestoreTimestamp> for DateTime<Utc> {
+    fn from(timestamp: FirestoreTimestamp) -> Self {
+        timestamp.0
+    }
+}
+
+impl fmt::Display for FirestoreTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for FirestoreTimestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        RawFirestoreTimestamp {
+            seconds: self.0.timestamp(),
+            nanoseconds: self.0.timestamp_subsec_nanos(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FirestoreTimestamp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawDate {
+            Iso(DateTime<Utc>),
+            Firestore(RawFirestoreTimestamp),
+        }
+
+        match RawDate::deserialize(deserializer)? {
+            RawDate::Iso(date) => Ok(Self(date)),
+            RawDate::Firestore(RawFirestoreTimestamp {
+                seconds,
+                nanoseconds,
+            }) => DateTime::from_timestamp(seconds, nanoseconds)
+                .map(Self)
+                .ok_or_else(|| serde::de::Error::custom("timestamp out of range")),
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for FirestoreTimestamp {
+    fn schema_name() -> String {
+        "FirestoreTimestamp".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> Schema {
+        RawFirestoreTimestamp::json_schema(gen)
+    }
+}
@@ -2,10 +2,10 @@
 //!
 //! This module provides the request and response models for typing indicators used in the Sendblue API.
 
-use phonenumber::PhoneNumber;
+use crate::models::PhoneNumber;
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-/*
-use crate::models::deserialize_phone_number; */
 
 /// Status of the typing indicator in the Sendblue API
 ///
@@ -14,6 +14,7 @@ use crate::models::deserialize_phone_number; */
 /// * `Sent` - The typing indicator was sent successfully
 /// * `Error` - An error occurred while sending the typing indicator
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[serde(rename_all = "UPPERCASE")]
 pub enum TypingIndicatorStatus {
     Sent,
@@ -27,9 +28,10 @@ pub enum TypingIndicatorStatus {
 /// - `status`: The status of the typing indicator you tried to send (this will either be SENT or ERROR)
 /// - `error_message`: The error message if the status is ERROR
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct TypingIndicatorResponse {
     /// The number you evaluated in E.164 format
-    /* #[serde(deserialize_with = "deserialize_phone_number")] */
     pub number: PhoneNumber,
     /// The status of the typing indicator you tried to send (this will either be SENT or ERROR)
     pub status: TypingIndicatorStatus,
@@ -46,16 +48,15 @@ pub struct TypingIndicatorResponse {
 ///
 /// ```
 /// use sendblue::models::TypingIndicator;
-/// use phonenumber::parse;
 ///
-/// let phone_number = parse(None, "+1234567890").unwrap();
+/// let phone_number = "+14155552671".parse().unwrap();
 /// let request = TypingIndicator {
 ///     number: phone_number,
 /// };
 /// ```
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct TypingIndicator {
     /// The phone number to send the typing indicator to
-    /* #[serde(deserialize_with = "deserialize_phone_number")] */
     pub number: PhoneNumber,
 }
@@ -3,6 +3,11 @@
 //! This module provides the data model for voice notes used in the Sendblue API.
 
 use crate::traits::Url;
+#[cfg(feature = "schemars")]
+use schemars::{
+    schema::{InstanceType, Schema, SchemaObject},
+    JsonSchema,
+};
 use serde::{Deserialize, Serialize};
 use url::Url as RawUrl;
 use validator::ValidationError;
@@ -59,3 +64,19 @@ impl<'de> Deserialize<'de> for VoiceNote {
         VoiceNote::new(s).map_err(serde::de::Error::custom)
     }
 }
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for VoiceNote {
+    fn schema_name() -> String {
+        "VoiceNote".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("uri".to_string()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
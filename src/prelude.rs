@@ -2,11 +2,23 @@
 //!
 //! This module re-exports commonly used items for convenience, including the Sendblue client,
 //! error types, and various models such as messages and URLs.
+//!
+//! ```
+//! use sendblue::prelude::*;
+//!
+//! let client = SendblueClient::new("your_api_key".into(), "your_api_secret".into());
+//! let message = MessageBuilder::new("+14155552671")
+//!     .content("Hello, world!".into())
+//!     .build()
+//!     .unwrap();
+//! ```
 
 pub use crate::errors::SendblueError;
 pub use crate::models::{
-    CallbackUrl, EvaluateService, EvaluateServiceBuilder, EvaluateServiceResponse, MediaUrl,
-    Message, MessageBuilder, MessageResponse, SendStyle, Status, TypingIndicatorResponse,
-    VoiceNote,
+    CallbackUrl, EvaluateService, EvaluateServiceBuilder, EvaluateServiceResponse,
+    GetMessagesParams, GetMessagesParamsBuilder, GetMessagesResponse, GroupId, GroupMessage,
+    GroupMessageResponse, MediaUrl, Message, MessageBuilder, MessageHandle, MessageResponse,
+    RetrievedMessage, SendStyle, Status, TypingIndicator, TypingIndicatorResponse, VoiceNote,
 };
 pub use crate::traits::Url;
+pub use crate::{SendblueClient, SendblueClientBuilder, SendblueConfig};
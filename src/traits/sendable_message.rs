@@ -4,4 +4,19 @@ use serde::Serialize;
 pub trait SendableMessage: Serialize {
     fn endpoint() -> &'static str;
     type ResponseType: for<'de> serde::Deserialize<'de>;
+
+    /// Describes the recipient of the message, used for tracing
+    fn recipient(&self) -> Option<String> {
+        None
+    }
+
+    /// Key used by the client to skip sending the same message twice
+    fn idempotency_key(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the recipient's number if the response says they have opted out
+    fn opted_out_recipient(_response: &Self::ResponseType) -> Option<String> {
+        None
+    }
 }